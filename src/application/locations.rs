@@ -0,0 +1,41 @@
+//! API for endpoints under `api/application/locations`
+
+use crate::application::ApplicationClient;
+use crate::structs::PteroList;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// A location, which groups nodes together
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct Location {
+    /// The ID of this location
+    pub id: u64,
+    /// The short code of this location
+    pub short: String,
+    /// The description of this location
+    pub long: Option<String>,
+    /// When this location was created
+    #[serde(
+        deserialize_with = "crate::structs::iso_time",
+        serialize_with = "crate::structs::serialize_iso_time"
+    )]
+    pub created_at: OffsetDateTime,
+    /// When this location was last updated
+    #[serde(
+        deserialize_with = "crate::structs::iso_time",
+        serialize_with = "crate::structs::serialize_iso_time"
+    )]
+    pub updated_at: OffsetDateTime,
+}
+
+impl ApplicationClient {
+    /// Lists the locations on the panel
+    pub async fn list_locations(&self) -> crate::Result<Vec<Location>> {
+        self.client
+            .request::<PteroList<Location>>(Method::GET, "locations")
+            .await
+            .map(|locations| locations.data)
+    }
+}
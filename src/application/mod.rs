@@ -0,0 +1,80 @@
+//! Pterodactyl Application API implementation, for endpoints under `api/application`. These
+//! endpoints require an application API key, created from the admin area of the panel
+
+use crate::client::{Client, RateLimits};
+use std::sync::{Arc, RwLock};
+
+pub mod locations;
+pub mod nodes;
+pub mod servers;
+
+/// A Pterodactyl application client, to make requests to the Pterodactyl application API.
+///
+/// Application clients are cheap to clone, and clones share the same rate limit information
+#[derive(Debug, Clone)]
+pub struct ApplicationClient {
+    pub(crate) client: Client,
+}
+
+impl ApplicationClient {
+    /// Gets the rate limit information after the previous request
+    pub fn get_rate_limits(&self) -> Option<RateLimits> {
+        self.client.get_rate_limits()
+    }
+}
+
+/// A builder for an application client
+#[derive(Debug)]
+pub struct ApplicationClientBuilder {
+    url: String,
+    api_prefix: String,
+    client: Option<reqwest::Client>,
+    api_key: String,
+}
+
+impl ApplicationClientBuilder {
+    /// Creates a new application client builder, connecting to the given URL where a Pterodactyl
+    /// server is hosted, using the given application API key for authentication. By default,
+    /// requests are made to the `api/application/` path under this URL, which can be changed using
+    /// [`ApplicationClientBuilder::with_api_prefix`]
+    pub fn new(url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            api_prefix: "api/application/".to_owned(),
+            client: None,
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Overrides the path appended to the URL to reach the application API, which is
+    /// `api/application/` by default
+    pub fn with_api_prefix(self, api_prefix: impl Into<String>) -> Self {
+        Self {
+            api_prefix: api_prefix.into(),
+            ..self
+        }
+    }
+
+    /// Uses the specified [`reqwest::Client`] for requests instead of making a default one
+    pub fn with_client(self, client: reqwest::Client) -> Self {
+        Self {
+            client: Some(client),
+            ..self
+        }
+    }
+
+    /// Builds an application client
+    pub fn build(self) -> ApplicationClient {
+        ApplicationClient {
+            client: Client {
+                url: crate::http::api_url(&self.url, &self.api_prefix),
+                client: self.client.unwrap_or_default(),
+                api_key: Some(self.api_key),
+                rate_limits: Arc::new(RwLock::new(None)),
+                etag_cache: None,
+                concurrency_limit: None,
+                on_request_complete: None,
+            },
+        }
+    }
+}
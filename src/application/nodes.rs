@@ -0,0 +1,111 @@
+//! API for endpoints under `api/application/nodes`
+
+use crate::application::ApplicationClient;
+use crate::structs::{PteroList, PteroObject};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// A node which servers run on
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct Node {
+    /// The ID of this node
+    pub id: u64,
+    /// The UUID of this node
+    pub uuid: Uuid,
+    /// Whether this node is public, allowing automatic deployment of servers to it
+    pub public: bool,
+    /// The name of this node
+    pub name: String,
+    /// The description of this node
+    pub description: Option<String>,
+    /// The ID of the location of this node
+    pub location_id: u64,
+    /// The domain name of this node
+    pub fqdn: String,
+    /// The scheme used to connect to this node, either `http` or `https`
+    pub scheme: String,
+    /// Whether this node is behind a proxy
+    pub behind_proxy: bool,
+    /// Whether this node is under maintenance
+    pub maintenance_mode: bool,
+    /// The total memory of this node in MiB
+    pub memory: u64,
+    /// The percentage of memory which can be allocated over the total, or -1 for unlimited
+    pub memory_overallocate: i64,
+    /// The total disk space of this node in MiB
+    pub disk: u64,
+    /// The percentage of disk space which can be allocated over the total, or -1 for unlimited
+    pub disk_overallocate: i64,
+    /// The maximum upload size in MiB
+    pub upload_size: u64,
+    /// The port the daemon listens on
+    pub daemon_listen: u16,
+    /// The port the daemon listens on for SFTP
+    pub daemon_sftp: u16,
+    /// The base directory where server files are stored
+    pub daemon_base: String,
+    /// The resources allocated to servers on this node. May not be present on older panels
+    #[serde(default)]
+    pub allocated_resources: Option<NodeAllocatedResources>,
+    /// When this node was created
+    #[serde(
+        deserialize_with = "crate::structs::iso_time",
+        serialize_with = "crate::structs::serialize_iso_time"
+    )]
+    pub created_at: OffsetDateTime,
+    /// When this node was last updated
+    #[serde(
+        deserialize_with = "crate::structs::iso_time",
+        serialize_with = "crate::structs::serialize_iso_time"
+    )]
+    pub updated_at: OffsetDateTime,
+}
+
+/// The resources allocated to servers on a node
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct NodeAllocatedResources {
+    /// The memory allocated to servers in MiB
+    pub memory: u64,
+    /// The disk space allocated to servers in MiB
+    pub disk: u64,
+}
+
+impl Node {
+    /// Gets the memory in MiB which has not yet been allocated to servers, ignoring
+    /// overallocation, or `None` if the allocated resources are not known
+    pub fn memory_free(&self) -> Option<i64> {
+        self.allocated_resources
+            .as_ref()
+            .map(|allocated| self.memory as i64 - allocated.memory as i64)
+    }
+
+    /// Gets the disk space in MiB which has not yet been allocated to servers, ignoring
+    /// overallocation, or `None` if the allocated resources are not known
+    pub fn disk_free(&self) -> Option<i64> {
+        self.allocated_resources
+            .as_ref()
+            .map(|allocated| self.disk as i64 - allocated.disk as i64)
+    }
+}
+
+impl ApplicationClient {
+    /// Lists the nodes on the panel
+    pub async fn list_nodes(&self) -> crate::Result<Vec<Node>> {
+        self.client
+            .request::<PteroList<Node>>(Method::GET, "nodes")
+            .await
+            .map(|nodes| nodes.data)
+    }
+
+    /// Gets the node with the given ID
+    pub async fn get_node(&self, id: u64) -> crate::Result<Node> {
+        self.client
+            .request::<PteroObject<Node>>(Method::GET, &format!("nodes/{id}"))
+            .await
+            .map(|node| node.attributes)
+    }
+}
@@ -0,0 +1,519 @@
+//! API for endpoints under `api/application/servers`
+
+use crate::application::ApplicationClient;
+use crate::client::{ServerFeatureLimits, ServerStatus};
+use crate::structs::PteroObject;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// A server, as seen by the application API
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+#[non_exhaustive]
+pub struct ApplicationServer {
+    /// The internal ID of this server
+    pub id: u64,
+    /// The external ID of this server, if set
+    pub external_id: Option<String>,
+    /// The UUID of this server
+    pub uuid: Uuid,
+    /// The short identifier of this server, as used by the client API
+    pub identifier: String,
+    /// The name of this server
+    pub name: String,
+    /// The description of this server
+    #[serde(deserialize_with = "crate::structs::default_on_null")]
+    pub description: String,
+    /// The current status of this server
+    pub status: Option<ServerStatus>,
+    /// Whether this server is suspended
+    pub suspended: bool,
+    /// The virtual hardware limits for this server
+    pub limits: ApplicationServerLimits,
+    /// Limits for various features on this server
+    pub feature_limits: ServerFeatureLimits,
+    /// The ID of the user who owns this server
+    pub user: u64,
+    /// The ID of the node this server is running on
+    pub node: u64,
+    /// The ID of the primary allocation of this server
+    pub allocation: u64,
+    /// The ID of the nest of this server
+    pub nest: u64,
+    /// The ID of the egg of this server
+    pub egg: u64,
+    /// The container configuration of this server
+    pub container: ServerContainer,
+    /// When this server was last updated
+    #[serde(
+        deserialize_with = "crate::structs::iso_time",
+        serialize_with = "crate::structs::serialize_iso_time"
+    )]
+    pub updated_at: OffsetDateTime,
+    /// When this server was created
+    #[serde(
+        deserialize_with = "crate::structs::iso_time",
+        serialize_with = "crate::structs::serialize_iso_time"
+    )]
+    pub created_at: OffsetDateTime,
+}
+
+/// Virtual hardware limits for a server, as seen by the application API
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+#[non_exhaustive]
+pub struct ApplicationServerLimits {
+    /// Maximum memory in MiB, or 0 for unlimited
+    pub memory: u64,
+    /// Maximum swap memory in MiB, 0 for none, or -1 for unlimited
+    pub swap: i64,
+    /// Maximum disk space in MiB, or 0 for unlimited
+    pub disk: u64,
+    /// The I/O weight
+    pub io: u32,
+    /// Maximum CPU usage as a percentage, or 0 for unlimited
+    pub cpu: f32,
+    /// Which threads this server should run on, or `None` for unrestricted
+    pub threads: Option<String>,
+    /// Whether the out of memory killer is disabled on this server
+    #[serde(default)]
+    pub oom_disabled: bool,
+}
+
+/// The container configuration of a server
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct ServerContainer {
+    /// The startup command for this server
+    pub startup_command: String,
+    /// The docker image for this server
+    pub image: String,
+    /// The environment variables for this server
+    #[serde(deserialize_with = "crate::structs::default_on_null")]
+    pub environment: HashMap<String, serde_json::Value>,
+}
+
+/// The virtual hardware limits to create a server with
+#[derive(Debug, Default, Serialize, PartialEq, Eq, Hash, Clone)]
+pub struct ServerLimitsParams {
+    memory: u64,
+    swap: i64,
+    disk: u64,
+    io: u32,
+    cpu: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    threads: Option<String>,
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    oom_disabled: bool,
+}
+
+impl ServerLimitsParams {
+    /// Creates the default limits, which are unlimited memory, disk and CPU, no swap, and the
+    /// default I/O weight
+    pub fn new() -> Self {
+        ServerLimitsParams {
+            io: 500,
+            ..ServerLimitsParams::default()
+        }
+    }
+
+    /// Sets the maximum memory in MiB, or 0 for unlimited
+    pub fn with_memory(self, memory: u64) -> Self {
+        ServerLimitsParams { memory, ..self }
+    }
+
+    /// Sets the maximum swap memory in MiB, 0 for none, or -1 for unlimited
+    pub fn with_swap(self, swap: i64) -> Self {
+        ServerLimitsParams { swap, ..self }
+    }
+
+    /// Sets the maximum disk space in MiB, or 0 for unlimited
+    pub fn with_disk(self, disk: u64) -> Self {
+        ServerLimitsParams { disk, ..self }
+    }
+
+    /// Sets the I/O weight, between 10 and 1000
+    pub fn with_io(self, io: u32) -> Self {
+        ServerLimitsParams { io, ..self }
+    }
+
+    /// Sets the maximum CPU usage as a percentage, or 0 for unlimited
+    pub fn with_cpu(self, cpu: u32) -> Self {
+        ServerLimitsParams { cpu, ..self }
+    }
+
+    /// Sets which threads the server should run on, for example `"0-1,3"`
+    pub fn with_threads(self, threads: impl Into<String>) -> Self {
+        ServerLimitsParams {
+            threads: Some(threads.into()),
+            ..self
+        }
+    }
+
+    /// Disables the out of memory killer on the server
+    pub fn set_oom_disabled(self) -> Self {
+        ServerLimitsParams {
+            oom_disabled: true,
+            ..self
+        }
+    }
+}
+
+/// The feature limits to create a server with
+#[derive(Debug, Default, Serialize, PartialEq, Eq, Hash, Clone)]
+pub struct FeatureLimitsParams {
+    databases: Option<u64>,
+    allocations: Option<u64>,
+    backups: Option<u64>,
+}
+
+impl FeatureLimitsParams {
+    /// Creates the default feature limits, which leaves all limits unset
+    pub fn new() -> Self {
+        FeatureLimitsParams::default()
+    }
+
+    /// Sets the maximum number of databases
+    pub fn with_databases(self, databases: u64) -> Self {
+        FeatureLimitsParams {
+            databases: Some(databases),
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of network allocations
+    pub fn with_allocations(self, allocations: u64) -> Self {
+        FeatureLimitsParams {
+            allocations: Some(allocations),
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of backups
+    pub fn with_backups(self, backups: u64) -> Self {
+        FeatureLimitsParams {
+            backups: Some(backups),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, PartialEq, Eq, Hash, Clone)]
+struct AllocationParams {
+    // None until set by `with_allocation`, which `create_server` checks for
+    default: Option<u64>,
+    additional: Vec<u64>,
+}
+
+/// The parameters to create a server
+#[derive(Debug, Serialize, PartialEq, Eq, Clone)]
+pub struct CreateServerParams {
+    name: String,
+    user: u64,
+    egg: u64,
+    docker_image: String,
+    startup: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    external_id: Option<String>,
+    environment: HashMap<String, String>,
+    limits: ServerLimitsParams,
+    feature_limits: FeatureLimitsParams,
+    allocation: AllocationParams,
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    start_on_completion: bool,
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    skip_scripts: bool,
+}
+
+impl CreateServerParams {
+    /// Creates the default server parameters with the given name, owner user ID, egg ID, docker
+    /// image and startup command. An allocation must also be set using
+    /// [`CreateServerParams::with_allocation`]
+    pub fn new(
+        name: impl Into<String>,
+        user: u64,
+        egg: u64,
+        docker_image: impl Into<String>,
+        startup: impl Into<String>,
+    ) -> Self {
+        CreateServerParams {
+            name: name.into(),
+            user,
+            egg,
+            docker_image: docker_image.into(),
+            startup: startup.into(),
+            description: None,
+            external_id: None,
+            environment: HashMap::new(),
+            limits: ServerLimitsParams::new(),
+            feature_limits: FeatureLimitsParams::new(),
+            allocation: AllocationParams::default(),
+            start_on_completion: false,
+            skip_scripts: false,
+        }
+    }
+
+    /// Sets the description of the server
+    pub fn with_description(self, description: impl Into<String>) -> Self {
+        CreateServerParams {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+
+    /// Sets the external ID of the server
+    pub fn with_external_id(self, external_id: impl Into<String>) -> Self {
+        CreateServerParams {
+            external_id: Some(external_id.into()),
+            ..self
+        }
+    }
+
+    /// Sets an environment variable for the server
+    pub fn with_environment_variable(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.environment.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets all the environment variables for the server
+    pub fn with_environment(self, environment: HashMap<String, String>) -> Self {
+        CreateServerParams {
+            environment,
+            ..self
+        }
+    }
+
+    /// Sets the virtual hardware limits of the server
+    pub fn with_limits(self, limits: ServerLimitsParams) -> Self {
+        CreateServerParams { limits, ..self }
+    }
+
+    /// Sets the feature limits of the server
+    pub fn with_feature_limits(self, feature_limits: FeatureLimitsParams) -> Self {
+        CreateServerParams {
+            feature_limits,
+            ..self
+        }
+    }
+
+    /// Sets the ID of the primary allocation of the server
+    pub fn with_allocation(self, allocation: u64) -> Self {
+        CreateServerParams {
+            allocation: AllocationParams {
+                default: Some(allocation),
+                ..self.allocation
+            },
+            ..self
+        }
+    }
+
+    /// Sets the IDs of additional allocations of the server. The primary allocation must also be
+    /// set using [`CreateServerParams::with_allocation`]
+    pub fn with_additional_allocations(self, additional: Vec<u64>) -> Self {
+        CreateServerParams {
+            allocation: AllocationParams {
+                additional,
+                ..self.allocation
+            },
+            ..self
+        }
+    }
+
+    /// Makes the server start once it has finished installing
+    pub fn set_start_on_completion(self) -> Self {
+        CreateServerParams {
+            start_on_completion: true,
+            ..self
+        }
+    }
+
+    /// Skips running the egg install script when the server is created
+    pub fn set_skip_scripts(self) -> Self {
+        CreateServerParams {
+            skip_scripts: true,
+            ..self
+        }
+    }
+}
+
+/// The startup configuration to update a server with
+#[derive(Debug, Default, Serialize, PartialEq, Eq, Clone)]
+pub struct ServerStartupParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    startup: Option<String>,
+    environment: HashMap<String, serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    egg: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    skip_scripts: bool,
+}
+
+impl ServerStartupParams {
+    /// Creates the default startup parameters, which don't change the startup command, egg or
+    /// docker image. The panel validates the environment against the variables of the egg, so
+    /// values for the required variables must be set, or the parameters created from an existing
+    /// server using [`From<ApplicationServer>`]
+    pub fn new() -> Self {
+        ServerStartupParams::default()
+    }
+
+    /// Sets the startup command of the server. Variables in the command are written as
+    /// `{{VARIABLE}}`
+    pub fn with_startup(self, startup: impl Into<String>) -> Self {
+        ServerStartupParams {
+            startup: Some(startup.into()),
+            ..self
+        }
+    }
+
+    /// Sets an environment variable for the server
+    pub fn with_environment_variable(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.environment
+            .insert(key.into(), serde_json::Value::String(value.into()));
+        self
+    }
+
+    /// Sets the ID of the egg of the server
+    pub fn with_egg(self, egg: u64) -> Self {
+        ServerStartupParams {
+            egg: Some(egg),
+            ..self
+        }
+    }
+
+    /// Sets the docker image of the server
+    pub fn with_image(self, image: impl Into<String>) -> Self {
+        ServerStartupParams {
+            image: Some(image.into()),
+            ..self
+        }
+    }
+
+    /// Skips running the egg install script if the egg is changed
+    pub fn set_skip_scripts(self) -> Self {
+        ServerStartupParams {
+            skip_scripts: true,
+            ..self
+        }
+    }
+}
+
+impl From<ApplicationServer> for ServerStartupParams {
+    fn from(value: ApplicationServer) -> Self {
+        ServerStartupParams {
+            startup: Some(value.container.startup_command),
+            environment: value.container.environment,
+            egg: Some(value.egg),
+            image: Some(value.container.image),
+            skip_scripts: false,
+        }
+    }
+}
+
+impl ApplicationClient {
+    /// Creates a server with the given parameters. Returns [`crate::Error::MissingField`] if the
+    /// name or allocation is missing
+    ///
+    /// ```no_run
+    /// # use pterodactyl_api::application::ApplicationClient;
+    /// # use pterodactyl_api::application::servers::{CreateServerParams, ServerLimitsParams};
+    /// # let client: ApplicationClient = todo!();
+    /// # async {
+    /// client
+    ///     .create_server(
+    ///         CreateServerParams::new("My Server", 1, 5, "ghcr.io/pterodactyl/yolks:java_17", "java -jar server.jar")
+    ///             .with_environment_variable("SERVER_JARFILE", "server.jar")
+    ///             .with_limits(ServerLimitsParams::new().with_memory(2048).with_disk(10240))
+    ///             .with_allocation(17),
+    ///     )
+    ///     .await?;
+    /// # Ok::<_, pterodactyl_api::Error>(())
+    /// # };
+    /// ```
+    pub async fn create_server(
+        &self,
+        params: CreateServerParams,
+    ) -> crate::Result<ApplicationServer> {
+        if params.name.is_empty() {
+            return Err(crate::Error::MissingField("name"));
+        }
+        if params.allocation.default.is_none() {
+            return Err(crate::Error::MissingField("allocation"));
+        }
+        self.client
+            .request_with_body::<PteroObject<ApplicationServer>, _>(
+                Method::POST,
+                "servers",
+                &params,
+            )
+            .await
+            .map(|server| server.attributes)
+    }
+
+    /// Updates the startup configuration of the server with the given ID, then returns the
+    /// updated server
+    ///
+    /// ```no_run
+    /// # use pterodactyl_api::application::ApplicationClient;
+    /// # use pterodactyl_api::application::servers::{ApplicationServer, ServerStartupParams};
+    /// # let client: ApplicationClient = todo!();
+    /// # let server: ApplicationServer = todo!();
+    /// # async {
+    /// client
+    ///     .update_server_startup(
+    ///         server.id,
+    ///         ServerStartupParams::from(server).with_startup("java -Xmx{{SERVER_MEMORY}}M -jar {{SERVER_JARFILE}}"),
+    ///     )
+    ///     .await?;
+    /// # Ok::<_, pterodactyl_api::Error>(())
+    /// # };
+    /// ```
+    pub async fn update_server_startup(
+        &self,
+        id: u64,
+        params: ServerStartupParams,
+    ) -> crate::Result<ApplicationServer> {
+        self.client
+            .request_with_body::<PteroObject<ApplicationServer>, _>(
+                Method::PATCH,
+                &format!("servers/{}/startup", id),
+                &params,
+            )
+            .await
+            .map(|server| server.attributes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::application::servers::CreateServerParams;
+    use crate::application::ApplicationClientBuilder;
+
+    #[tokio::test]
+    async fn test_create_server_requires_allocation() {
+        // the request fails before anything is sent, so the URL is never connected to
+        let client = ApplicationClientBuilder::new("http://127.0.0.1:1", "test-key").build();
+        let params = CreateServerParams::new("Test", 1, 1, "image", "startup")
+            .with_additional_allocations(vec![2, 3]);
+        assert!(matches!(
+            client.create_server(params.clone()).await,
+            Err(crate::Error::MissingField("allocation"))
+        ));
+        assert_eq!(
+            serde_json::to_value(params.with_allocation(1)).unwrap()["allocation"],
+            serde_json::json!({"default": 1, "additional": [2, 3]})
+        );
+    }
+}
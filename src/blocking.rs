@@ -0,0 +1,569 @@
+//! A blocking client API, for use in programs which don't use async. The blocking client mirrors
+//! the async client in [`crate::client`], except for the websocket and raw request APIs.
+//!
+//! Each blocking client owns its own single-threaded runtime, on which requests are run. Blocking
+//! clients must therefore not be used from within an async runtime, which will panic.
+
+use crate::client::account::{
+    Account, Account2fa, ApiKey, ApiKeyParams, CreatedApiKey, RecoveryTokens,
+};
+use crate::client::backups::{Backup, BackupParams};
+use crate::client::databases::{DatabaseParams, ServerDatabase};
+use crate::client::files::PteroFile;
+use crate::client::network::Allocation;
+use crate::client::schedules::{Schedule, ScheduleParams, ScheduleTask, TaskParams};
+use crate::client::startup::{StartupData, Variable};
+use crate::client::users::User;
+use crate::client::{
+    ListOptions, Permission, PermissionGroup, PowerSignal, RateLimits, ServerInclude,
+    ServerResources, ServerState, ServerStruct,
+};
+use bytes::Bytes;
+use reqwest::{Body, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use tokio::sync::Semaphore;
+use uuid::Uuid;
+
+macro_rules! blocking_methods {
+    ($path:literal; $($(#[$attr:meta])* fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        $(
+            #[doc = concat!("Blocking version of [`", $path, "::", stringify!($name), "`]")]
+            $(#[$attr])*
+            pub fn $name(&self $(, $arg: $ty)*) -> $ret {
+                self.runtime.block_on(self.inner.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+/// A blocking Pterodactyl client, to make requests to the Pterodactyl client API.
+///
+/// Clients are cheap to clone, and clones share the same runtime, rate limit information and
+/// cache
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: crate::client::Client,
+    runtime: Arc<Runtime>,
+}
+
+impl Client {
+    /// Gets the rate limit information after the previous request
+    pub fn get_rate_limits(&self) -> Option<RateLimits> {
+        self.inner.get_rate_limits()
+    }
+
+    /// Gets the server with the given ID
+    pub fn get_server(&self, id: impl Into<String>) -> Server<'_> {
+        Server {
+            inner: self.inner.get_server(id),
+            runtime: &self.runtime,
+        }
+    }
+
+    /// Gets the server with the given UUID
+    pub fn get_server_by_uuid(&self, uuid: Uuid) -> Server<'_> {
+        Server {
+            inner: self.inner.get_server_by_uuid(uuid),
+            runtime: &self.runtime,
+        }
+    }
+
+    /// Blocking version of [`crate::client::Client::try_get_server`]
+    pub fn try_get_server(&self, id: impl Into<String>) -> crate::Result<Option<Server<'_>>> {
+        let server = self.get_server(id);
+        Ok(server.exists()?.then_some(server))
+    }
+
+    blocking_methods! {
+        "crate::client::Client";
+        fn list_servers(&self) -> crate::Result<Vec<ServerStruct>>;
+        fn list_servers_with_options(
+            &self,
+            options: impl Into<ListOptions>
+        ) -> crate::Result<Vec<ServerStruct>>;
+        fn list_servers_owned(&self) -> crate::Result<Vec<ServerStruct>>;
+        fn list_servers_all_admin(&self) -> crate::Result<Vec<ServerStruct>>;
+        fn get_all_resources(
+            &self
+        ) -> crate::Result<Vec<(String, crate::Result<ServerResources>)>>;
+        fn get_permissions(&self) -> crate::Result<HashMap<String, PermissionGroup>>;
+        fn get_permissions_typed(&self) -> crate::Result<HashMap<Permission, String>>;
+        fn get_account_details(&self) -> crate::Result<Account>;
+        fn get_account_2fa_details(&self) -> crate::Result<Account2fa>;
+        fn enable_2fa(&self, token: impl Into<String>) -> crate::Result<RecoveryTokens>;
+        fn disable_2fa(&self, password: impl Into<String>) -> crate::Result<()>;
+        fn update_email(
+            &self,
+            email: impl Into<String>,
+            password: impl Into<String>
+        ) -> crate::Result<()>;
+        fn update_password(
+            &self,
+            current_password: impl Into<String>,
+            new_password: impl Into<String>
+        ) -> crate::Result<()>;
+        fn get_api_keys(&self) -> crate::Result<Vec<ApiKey>>;
+        fn create_api_key(&self, description: impl Into<String>) -> crate::Result<CreatedApiKey>;
+        fn create_api_key_with_allowed_ips(
+            &self,
+            description: impl Into<String>,
+            allowed_ips: Vec<String>
+        ) -> crate::Result<CreatedApiKey>;
+        fn create_api_key_with_params(
+            &self,
+            params: impl Into<ApiKeyParams>
+        ) -> crate::Result<CreatedApiKey>;
+        fn delete_api_key(&self, id: impl Into<String>) -> crate::Result<()>;
+    }
+}
+
+/// A builder for a blocking client
+#[derive(Debug)]
+pub struct ClientBuilder {
+    inner: crate::client::ClientBuilder,
+}
+
+impl ClientBuilder {
+    /// Creates a new client builder. See [`crate::client::ClientBuilder::new`]
+    pub fn new(url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self {
+            inner: crate::client::ClientBuilder::new(url, api_key),
+        }
+    }
+
+    /// See [`crate::client::ClientBuilder::with_api_prefix`]
+    pub fn with_api_prefix(self, api_prefix: impl Into<String>) -> Self {
+        Self {
+            inner: self.inner.with_api_prefix(api_prefix),
+        }
+    }
+
+    /// See [`crate::client::ClientBuilder::with_client`]
+    pub fn with_client(self, client: reqwest::Client) -> Self {
+        Self {
+            inner: self.inner.with_client(client),
+        }
+    }
+
+    /// See [`crate::client::ClientBuilder::with_authenticated_client`]
+    pub fn with_authenticated_client(self, client: reqwest::Client) -> Self {
+        Self {
+            inner: self.inner.with_authenticated_client(client),
+        }
+    }
+
+    /// See [`crate::client::ClientBuilder::with_etag_cache`]
+    pub fn with_etag_cache(self) -> Self {
+        Self {
+            inner: self.inner.with_etag_cache(),
+        }
+    }
+
+    /// See [`crate::client::ClientBuilder::with_concurrency_limit`]
+    pub fn with_concurrency_limit(self, limit: usize) -> Self {
+        Self {
+            inner: self.inner.with_concurrency_limit(limit),
+        }
+    }
+
+    /// See [`crate::client::ClientBuilder::with_semaphore`]
+    pub fn with_semaphore(self, semaphore: Arc<Semaphore>) -> Self {
+        Self {
+            inner: self.inner.with_semaphore(semaphore),
+        }
+    }
+
+    /// See [`crate::client::ClientBuilder::on_request_complete`]
+    pub fn on_request_complete(
+        self,
+        callback: impl Fn(&str, StatusCode, Duration) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            inner: self.inner.on_request_complete(callback),
+        }
+    }
+
+    /// See [`crate::client::ClientBuilder::disable_compression`]
+    pub fn disable_compression(self) -> Self {
+        Self {
+            inner: self.inner.disable_compression(),
+        }
+    }
+
+    /// See [`crate::client::ClientBuilder::danger_accept_invalid_certs`]
+    pub fn danger_accept_invalid_certs(self, accept_invalid_certs: bool) -> Self {
+        Self {
+            inner: self.inner.danger_accept_invalid_certs(accept_invalid_certs),
+        }
+    }
+
+    /// Builds a blocking client. Returns [`crate::Error::Io`] if the runtime could not be created
+    pub fn build(self) -> crate::Result<Client> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Client {
+            inner: self.inner.build(),
+            runtime: Arc::new(runtime),
+        })
+    }
+}
+
+/// Represents a Pterodactyl server, with which blocking requests specific to a server can be made
+#[derive(Debug)]
+pub struct Server<'a> {
+    inner: crate::client::Server<'a>,
+    runtime: &'a Runtime,
+}
+
+impl Server<'_> {
+    blocking_methods! {
+        "crate::client::Server";
+        fn get_details(&self) -> crate::Result<ServerStruct>;
+        fn get_details_with_includes(
+            &self,
+            includes: &[ServerInclude]
+        ) -> crate::Result<ServerStruct>;
+        fn exists(&self) -> crate::Result<bool>;
+        fn can(&self, permission: Permission) -> crate::Result<bool>;
+        fn get_resources(&self) -> crate::Result<ServerResources>;
+        fn get_state(&self) -> crate::Result<ServerState>;
+        fn wait_for_state(
+            &self,
+            target: ServerState,
+            timeout: Duration,
+            poll_interval: Duration
+        ) -> crate::Result<()>;
+        fn wait_for_transfer_complete(
+            &self,
+            timeout: Duration,
+            poll_interval: Duration
+        ) -> crate::Result<()>;
+        fn send_command(&self, command: impl Into<String>) -> crate::Result<()>;
+        fn send_power_signal(&self, signal: PowerSignal) -> crate::Result<()>;
+
+        fn list_network_allocations(&self) -> crate::Result<Vec<Allocation>>;
+        fn create_network_allocation(&self) -> crate::Result<Allocation>;
+        fn create_network_allocation_with_notes(
+            &self,
+            notes: impl Into<String>
+        ) -> crate::Result<Allocation>;
+        fn set_network_allocation_notes(
+            &self,
+            allocation_id: u64,
+            notes: impl Into<String>
+        ) -> crate::Result<Allocation>;
+        fn clear_network_allocation_notes(&self, allocation_id: u64) -> crate::Result<Allocation>;
+        fn set_network_allocation_primary(&self, allocation_id: u64) -> crate::Result<Allocation>;
+        fn delete_network_allocation(&self, allocation_id: u64) -> crate::Result<()>;
+
+        fn list_users(&self) -> crate::Result<Vec<User>>;
+        fn add_user(
+            &self,
+            email: impl Into<String>,
+            permissions: Vec<String>
+        ) -> crate::Result<User>;
+        fn get_user(&self, id: Uuid) -> crate::Result<User>;
+        fn set_user_permissions(&self, id: Uuid, permissions: Vec<String>) -> crate::Result<User>;
+        fn delete_user(&self, id: Uuid) -> crate::Result<()>;
+
+        fn list_files(&self, directory: impl AsRef<str>) -> crate::Result<Vec<PteroFile>>;
+        fn search_files(
+            &self,
+            directory: impl AsRef<str>,
+            query: impl AsRef<str>
+        ) -> crate::Result<Vec<PteroFile>>;
+        fn find_files(
+            &self,
+            root: impl AsRef<str>,
+            predicate: impl FnMut(&str, &PteroFile) -> bool
+        ) -> crate::Result<Vec<(String, PteroFile)>>;
+        fn list_files_recursive(
+            &self,
+            root: impl AsRef<str>,
+            max_depth: Option<usize>
+        ) -> crate::Result<Vec<(String, PteroFile)>>;
+        fn glob(&self, pattern: impl AsRef<str>) -> crate::Result<Vec<(String, PteroFile)>>;
+        fn file_contents(&self, file: impl AsRef<str>) -> crate::Result<Bytes>;
+        fn file_contents_range(
+            &self,
+            file: impl AsRef<str>,
+            start: u64,
+            end: Option<u64>
+        ) -> crate::Result<Bytes>;
+        fn file_contents_text(&self, file: impl AsRef<str>) -> crate::Result<String>;
+        fn file_contents_text_lossy(&self, file: impl AsRef<str>) -> crate::Result<String>;
+        #[cfg(feature = "stream")]
+        fn download_file_with_progress(
+            &self,
+            file: impl AsRef<str>,
+            progress: impl FnMut(u64, Option<u64>)
+        ) -> crate::Result<Bytes>;
+        fn get_file_download_url(&self, file: impl AsRef<str>) -> crate::Result<String>;
+        fn rename_file(&self, from: impl Into<String>, to: impl Into<String>) -> crate::Result<()>;
+        fn rename_file_checked(
+            &self,
+            from: impl Into<String>,
+            to: impl Into<String>,
+            overwrite: bool
+        ) -> crate::Result<()>;
+        fn rename_files(&self, files: Vec<(String, String)>) -> crate::Result<()>;
+        fn create_file_copy(&self, file: impl Into<String>) -> crate::Result<()>;
+        fn copy_file(&self, from: impl Into<String>, to: impl Into<String>) -> crate::Result<()>;
+        fn write_file(&self, file: impl AsRef<str>, data: impl Into<Body>) -> crate::Result<()>;
+        fn write_file_with_content_type(
+            &self,
+            file: impl AsRef<str>,
+            data: impl Into<Body>,
+            content_type: impl AsRef<str>
+        ) -> crate::Result<()>;
+        #[cfg(feature = "stream")]
+        fn write_file_from_path(
+            &self,
+            file: impl AsRef<str>,
+            local: impl AsRef<std::path::Path>
+        ) -> crate::Result<()>;
+        #[cfg(feature = "stream")]
+        fn write_file_stream(
+            &self,
+            file: impl AsRef<str>,
+            stream: impl futures_core::Stream<Item = std::io::Result<Bytes>> + Send + 'static
+        ) -> crate::Result<()>;
+        #[cfg(feature = "stream")]
+        fn write_file_from_reader(
+            &self,
+            file: impl AsRef<str>,
+            reader: impl tokio::io::AsyncRead + Send + 'static
+        ) -> crate::Result<()>;
+        fn compress_file(&self, file: impl Into<String>) -> crate::Result<PteroFile>;
+        fn compress_files(
+            &self,
+            root: impl Into<String>,
+            files: Vec<String>
+        ) -> crate::Result<PteroFile>;
+        fn decompress_file(
+            &self,
+            file: impl Into<String>,
+            dest: impl Into<String>
+        ) -> crate::Result<()>;
+        fn decompress_file_relative(
+            &self,
+            file: impl Into<String>,
+            dest: impl Into<String>
+        ) -> crate::Result<()>;
+        fn decompress_file_and_list(
+            &self,
+            file: impl Into<String>,
+            dest: impl Into<String>
+        ) -> crate::Result<Vec<PteroFile>>;
+        fn decompress_file_and_wait(
+            &self,
+            file: impl Into<String>,
+            dest: impl Into<String>,
+            timeout: Duration,
+            poll_interval: Duration
+        ) -> crate::Result<()>;
+        fn delete_file(&self, file: impl Into<String>) -> crate::Result<()>;
+        fn delete_files(&self, files: Vec<String>) -> crate::Result<()>;
+        fn delete_directory_contents(&self, dir: impl AsRef<str>) -> crate::Result<()>;
+        fn delete_all_matching(
+            &self,
+            dir: impl AsRef<str>,
+            predicate: impl FnMut(&str, &PteroFile) -> bool
+        ) -> crate::Result<()>;
+        fn create_folder(&self, folder: impl Into<String>) -> crate::Result<()>;
+        fn create_folder_idempotent(&self, folder: impl Into<String>) -> crate::Result<()>;
+        fn get_files_upload_url(&self) -> crate::Result<String>;
+        fn upload_file(
+            &self,
+            directory: impl AsRef<str>,
+            file_name: impl Into<String>,
+            data: impl Into<Bytes>
+        ) -> crate::Result<()>;
+        fn upload_file_and_verify(
+            &self,
+            directory: impl AsRef<str>,
+            file_name: impl Into<String>,
+            data: impl Into<Bytes>
+        ) -> crate::Result<PteroFile>;
+
+        fn list_databases(&self) -> crate::Result<Vec<ServerDatabase>>;
+        fn list_databases_with_passwords(&self) -> crate::Result<Vec<ServerDatabase>>;
+        fn create_database(
+            &self,
+            name: impl Into<String>,
+            remote: impl Into<String>
+        ) -> crate::Result<ServerDatabase>;
+        fn create_database_with_params(
+            &self,
+            params: impl Into<DatabaseParams>
+        ) -> crate::Result<ServerDatabase>;
+        fn rotate_database_password(&self, id: impl Into<String>) -> crate::Result<ServerDatabase>;
+        fn delete_database(&self, id: impl Into<String>) -> crate::Result<()>;
+
+        fn list_backups(&self) -> crate::Result<Vec<Backup>>;
+        fn list_backups_with_options(
+            &self,
+            options: impl Into<ListOptions>
+        ) -> crate::Result<Vec<Backup>>;
+        fn create_backup(&self) -> crate::Result<Backup>;
+        fn create_backup_with_params(
+            &self,
+            options: impl Into<BackupParams>
+        ) -> crate::Result<Backup>;
+        fn get_backup(&self, id: Uuid) -> crate::Result<Backup>;
+        fn wait_for_backup(
+            &self,
+            id: Uuid,
+            timeout: Duration,
+            poll_interval: Duration
+        ) -> crate::Result<Backup>;
+        fn get_backup_download_link(&self, id: Uuid) -> crate::Result<String>;
+        #[cfg(feature = "stream")]
+        fn download_backup_to_path(
+            &self,
+            id: Uuid,
+            local: impl AsRef<std::path::Path>
+        ) -> crate::Result<u64>;
+        #[cfg(feature = "stream")]
+        fn export_backup_to_path(
+            &self,
+            params: impl Into<BackupParams>,
+            local: impl AsRef<std::path::Path>,
+            delete_after: bool,
+            timeout: Duration,
+            poll_interval: Duration
+        ) -> crate::Result<u64>;
+        fn delete_backup(&self, id: Uuid) -> crate::Result<()>;
+
+        fn rename(&self, name: impl Into<String>) -> crate::Result<()>;
+        fn rename_and_fetch(&self, name: impl Into<String>) -> crate::Result<ServerStruct>;
+        fn rename_with_description(
+            &self,
+            name: impl Into<String>,
+            description: impl Into<String>
+        ) -> crate::Result<()>;
+        fn set_description(&self, description: impl Into<String>) -> crate::Result<()>;
+        fn set_docker_image(&self, image: impl Into<String>) -> crate::Result<()>;
+        fn reinstall(&self) -> crate::Result<()>;
+        fn reinstall_and_wait(&self, timeout: Duration, poll_interval: Duration) -> crate::Result<()>;
+
+        fn get_startup_data(&self) -> crate::Result<StartupData>;
+        fn set_startup_command(&self, cmd: impl Into<String>) -> crate::Result<StartupData>;
+        fn get_startup_variable(
+            &self,
+            env_variable: impl AsRef<str>
+        ) -> crate::Result<Option<Variable>>;
+        fn set_startup_variable(
+            &self,
+            name: impl Into<String>,
+            value: impl Into<String>
+        ) -> crate::Result<Variable>;
+
+        fn list_schedules(&self) -> crate::Result<Vec<Schedule>>;
+        fn create_schedule(&self, schedule: impl Into<ScheduleParams>) -> crate::Result<Schedule>;
+        fn get_schedule(&self, id: u64) -> crate::Result<Schedule>;
+        fn update_schedule(
+            &self,
+            id: u64,
+            schedule: impl Into<ScheduleParams>
+        ) -> crate::Result<Schedule>;
+        fn set_schedule_active(&self, id: u64, active: bool) -> crate::Result<Schedule>;
+        fn is_schedule_processing(&self, id: u64) -> crate::Result<bool>;
+        fn reset_schedule(&self, id: u64) -> crate::Result<Schedule>;
+        fn delete_schedule(&self, id: u64) -> crate::Result<()>;
+        fn create_task(
+            &self,
+            schedule_id: u64,
+            task: impl Into<TaskParams>
+        ) -> crate::Result<ScheduleTask>;
+        fn update_task(
+            &self,
+            schedule_id: u64,
+            task_id: u64,
+            task: impl Into<TaskParams>
+        ) -> crate::Result<ScheduleTask>;
+        fn delete_task(&self, schedule_id: u64, task_id: u64) -> crate::Result<()>;
+    }
+
+    /// Blocking version of [`crate::client::Server::file_contents_json`]
+    pub fn file_contents_json<T: DeserializeOwned>(
+        &self,
+        file: impl AsRef<str>,
+    ) -> crate::Result<T> {
+        self.runtime.block_on(self.inner.file_contents_json(file))
+    }
+
+    /// Blocking version of [`crate::client::Server::write_file_json`]
+    pub fn write_file_json<T: Serialize + ?Sized>(
+        &self,
+        file: impl AsRef<str>,
+        value: &T,
+    ) -> crate::Result<()> {
+        self.runtime
+            .block_on(self.inner.write_file_json(file, value))
+    }
+
+    /// Blocking version of [`crate::client::Server::set_startup_variables`]
+    pub fn set_startup_variables<K, V>(
+        &self,
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Vec<(String, crate::Result<Variable>)>
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.runtime
+            .block_on(self.inner.set_startup_variables(vars))
+    }
+
+    /// Blocking version of [`crate::client::Server::power`]. Call [`PowerRequest::send`] or
+    /// [`PowerRequest::wait_for_completion`] on the returned request to send the signal
+    pub fn power(&self, signal: PowerSignal) -> PowerRequest<'_> {
+        PowerRequest {
+            server: self,
+            signal,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Blocking version of [`crate::client::PowerRequest`]
+#[derive(Debug)]
+#[must_use = "power requests do nothing unless sent"]
+pub struct PowerRequest<'a> {
+    server: &'a Server<'a>,
+    signal: PowerSignal,
+    poll_interval: Duration,
+}
+
+impl PowerRequest<'_> {
+    /// Blocking version of [`crate::client::PowerRequest::with_poll_interval`]
+    pub fn with_poll_interval(self, poll_interval: Duration) -> Self {
+        PowerRequest {
+            poll_interval,
+            ..self
+        }
+    }
+
+    /// Sends the power signal without waiting for the server to reach the resulting state
+    pub fn send(self) -> crate::Result<()> {
+        self.server.send_power_signal(self.signal)
+    }
+
+    /// Blocking version of [`crate::client::PowerRequest::wait_for_completion`]
+    pub fn wait_for_completion(self, timeout: Duration) -> crate::Result<()> {
+        self.server.runtime.block_on(
+            self.server
+                .inner
+                .power(self.signal)
+                .with_poll_interval(self.poll_interval)
+                .wait_for_completion(timeout),
+        )
+    }
+}
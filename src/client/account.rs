@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
 /// Contains information about your client account
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct Account {
     /// The account ID
@@ -28,7 +28,7 @@ pub struct Account {
 }
 
 /// Account 2fa information
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
 pub struct Account2fa {
     /// The TOTP QR code image to allow the setup of 2FA
     pub image_url_data: String,
@@ -36,15 +36,84 @@ pub struct Account2fa {
     pub secret: String,
 }
 
+/// The parameters to create an API key
+#[derive(Debug, Serialize, PartialEq, Eq, Hash, Clone)]
+pub struct ApiKeyParams {
+    description: String,
+    allowed_ips: Option<Vec<String>>,
+}
+
+impl ApiKeyParams {
+    /// Creates the default API key parameters with the given description
+    pub fn new(description: impl Into<String>) -> Self {
+        ApiKeyParams {
+            description: description.into(),
+            allowed_ips: None,
+        }
+    }
+
+    /// Only allows the given IPs to connect using the API key, unless the list is empty in which
+    /// case anyone can connect
+    pub fn with_allowed_ips(self, allowed_ips: Vec<String>) -> Self {
+        ApiKeyParams {
+            allowed_ips: Some(allowed_ips),
+            ..self
+        }
+    }
+}
+
+impl<T> From<T> for ApiKeyParams
+where
+    T: Into<String>,
+{
+    fn from(value: T) -> Self {
+        ApiKeyParams::new(value)
+    }
+}
+
+impl Account2fa {
+    /// Decodes [`Account2fa::image_url_data`] as a data URI, returning the MIME type and the raw
+    /// image bytes, so that the QR code can be displayed without a browser. Returns
+    /// [`crate::Error::InvalidDataUri`] if it is not a data URI, which is the case on panel
+    /// versions which return the `otpauth://` URL for the client to render instead
+    pub fn decode_image(&self) -> crate::Result<(String, Vec<u8>)> {
+        use base64::Engine;
+
+        let (header, data) = self
+            .image_url_data
+            .strip_prefix("data:")
+            .and_then(|uri| uri.split_once(','))
+            .ok_or(crate::Error::InvalidDataUri)?;
+        let (mime, is_base64) = match header.strip_suffix(";base64") {
+            Some(mime) => (mime, true),
+            None => (header, false),
+        };
+        let mime = if mime.is_empty() {
+            "text/plain;charset=US-ASCII"
+        } else {
+            mime
+        };
+        let data = if is_base64 {
+            base64::engine::general_purpose::STANDARD
+                .decode(data)
+                .ok()
+                .ok_or(crate::Error::InvalidDataUri)?
+        } else {
+            urlencoding::decode_binary(data.as_bytes()).into_owned()
+        };
+        Ok((mime.to_owned(), data))
+    }
+}
+
 /// A list of 2fa recovery tokens
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
 pub struct RecoveryTokens {
     /// The tokens
     pub tokens: Vec<String>,
 }
 
 /// An API key to allow access to this account via the API
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct ApiKey {
     /// The ID for the API key
@@ -62,7 +131,7 @@ pub struct ApiKey {
 }
 
 /// An API key that has just been created, which includes the token used to login
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct CreatedApiKey {
     /// Metadata about the API key
     pub key: ApiKey,
@@ -230,8 +299,7 @@ impl Client {
         &self,
         description: impl Into<String>,
     ) -> crate::Result<CreatedApiKey> {
-        self.create_api_key_with_optional_allowed_ips(description, None)
-            .await
+        self.create_api_key_with_params(description).await
     }
 
     /// Creates a new API key that can be used to connect with this account. Only the IPs listed
@@ -242,14 +310,32 @@ impl Client {
         description: impl Into<String>,
         allowed_ips: Vec<String>,
     ) -> crate::Result<CreatedApiKey> {
-        self.create_api_key_with_optional_allowed_ips(description, Some(allowed_ips))
-            .await
+        self.create_api_key_with_params(
+            ApiKeyParams::new(description).with_allowed_ips(allowed_ips),
+        )
+        .await
     }
 
-    async fn create_api_key_with_optional_allowed_ips(
+    /// Creates a new API key with the given parameters that can be used to connect with this
+    /// account. Returns the token needed to connect, and metadata about the created key
+    ///
+    /// ```no_run
+    /// # use pterodactyl_api::client::account::ApiKeyParams;
+    /// # use pterodactyl_api::client::Client;
+    /// # let client: Client = todo!();
+    /// # async {
+    /// client.create_api_key_with_params("Deploy Key").await?;
+    /// client
+    ///     .create_api_key_with_params(
+    ///         ApiKeyParams::new("CI Key").with_allowed_ips(vec!["10.0.0.1".to_owned()]),
+    ///     )
+    ///     .await?;
+    /// # Ok::<_, pterodactyl_api::Error>(())
+    /// # };
+    /// ```
+    pub async fn create_api_key_with_params(
         &self,
-        description: impl Into<String>,
-        allowed_ips: Option<Vec<String>>,
+        params: impl Into<ApiKeyParams>,
     ) -> crate::Result<CreatedApiKey> {
         #[derive(Deserialize)]
         struct Meta {
@@ -260,18 +346,10 @@ impl Client {
             attributes: ApiKey,
             meta: Meta,
         }
-        #[derive(Serialize)]
-        struct CreateApiKeyBody {
-            description: String,
-            allowed_ips: Option<Vec<String>>,
-        }
         self.request_with_body::<CreatedApiKeyObj, _>(
             Method::POST,
             "account/api-keys",
-            &CreateApiKeyBody {
-                description: description.into(),
-                allowed_ips,
-            },
+            &params.into(),
         )
         .await
         .map(|obj| CreatedApiKey {
@@ -287,3 +365,34 @@ impl Client {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::Account2fa;
+
+    fn account_2fa(image_url_data: &str) -> Account2fa {
+        Account2fa {
+            image_url_data: image_url_data.to_owned(),
+            secret: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_decode_image() {
+        assert_eq!(
+            account_2fa("data:image/png;base64,iVBORw==")
+                .decode_image()
+                .unwrap(),
+            ("image/png".to_owned(), vec![0x89, b'P', b'N', b'G'])
+        );
+        assert_eq!(
+            account_2fa("data:image/svg+xml,%3Csvg%2F%3E")
+                .decode_image()
+                .unwrap(),
+            ("image/svg+xml".to_owned(), b"<svg/>".to_vec())
+        );
+        assert!(account_2fa("otpauth://totp/Panel:user?secret=ABC")
+            .decode_image()
+            .is_err());
+    }
+}
@@ -1,15 +1,16 @@
 //! API for endpoints under `api/client/servers/{server}/backups`
 
-use crate::client::Server;
+use crate::client::{ListOptions, Server};
 use crate::http::EmptyBody;
 use crate::structs::{PteroList, PteroObject};
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
 /// A backup created on a server
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct Backup {
     /// The backup ID
@@ -23,15 +24,80 @@ pub struct Backup {
     /// The size of this backup in bytes
     pub bytes: u64,
     /// When this backup was created
-    #[serde(deserialize_with = "crate::structs::iso_time")]
+    #[serde(
+        deserialize_with = "crate::structs::iso_time",
+        serialize_with = "crate::structs::serialize_iso_time"
+    )]
     pub created_at: OffsetDateTime,
     /// When this backup was completed
-    #[serde(deserialize_with = "crate::structs::optional_iso_time")]
+    #[serde(
+        deserialize_with = "crate::structs::optional_iso_time",
+        serialize_with = "crate::structs::serialize_optional_iso_time"
+    )]
     pub completed_at: Option<OffsetDateTime>,
+    /// Whether this backup completed successfully. Failed backups also have
+    /// [`Backup::completed_at`] set, so this must be checked before using a completed backup
+    pub is_successful: bool,
     /// Whether the backup is locked
     pub is_locked: bool,
 }
 
+impl Backup {
+    /// Verifies that the given data, such as a downloaded backup archive, matches the checksum of
+    /// this backup. Returns `None` if this backup has no checksum, or if the checksum algorithm is
+    /// not supported. Supported algorithms are `sha1` and `sha256`
+    #[cfg(feature = "checksum")]
+    pub fn verify_checksum(&self, data: &[u8]) -> Option<bool> {
+        let mut hasher = ChecksumHasher::new(self.checksum.as_deref()?)?;
+        hasher.update(data);
+        Some(hasher.matches())
+    }
+}
+
+#[cfg(feature = "checksum")]
+enum ChecksumHasher<'a> {
+    Sha1(sha1::Sha1, &'a str),
+    Sha256(sha2::Sha256, &'a str),
+}
+
+#[cfg(feature = "checksum")]
+impl<'a> ChecksumHasher<'a> {
+    fn new(checksum: &'a str) -> Option<Self> {
+        use sha1::Digest;
+
+        let (algorithm, expected) = checksum.split_once(':')?;
+        match algorithm.to_ascii_lowercase().as_str() {
+            "sha1" => Some(Self::Sha1(sha1::Sha1::new(), expected.trim())),
+            "sha256" => Some(Self::Sha256(sha2::Sha256::new(), expected.trim())),
+            _ => None,
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        use sha1::Digest;
+
+        match self {
+            Self::Sha1(hasher, _) => hasher.update(data),
+            Self::Sha256(hasher, _) => hasher.update(data),
+        }
+    }
+
+    fn matches(self) -> bool {
+        use sha1::Digest;
+        use std::fmt::Write;
+
+        let (actual, expected) = match self {
+            Self::Sha1(hasher, expected) => (hasher.finalize().to_vec(), expected),
+            Self::Sha256(hasher, expected) => (hasher.finalize().to_vec(), expected),
+        };
+        let actual = actual.iter().fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        });
+        actual.eq_ignore_ascii_case(expected)
+    }
+}
+
 /// The parameters to create a backup
 #[derive(Debug, Default, Serialize, PartialEq, Eq, Hash, Clone)]
 pub struct BackupParams {
@@ -39,6 +105,8 @@ pub struct BackupParams {
     name: Option<String>,
     #[serde(skip_serializing_if = "core::ops::Not::not")]
     is_locked: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ignored: Option<String>,
 }
 
 impl BackupParams {
@@ -55,6 +123,15 @@ impl BackupParams {
         }
     }
 
+    /// Sets the files to ignore while creating the backup, in the same format as a
+    /// `.pteroignore` file, e.g. `world/tmp` or `*.log`
+    pub fn with_ignored_files(self, ignored_files: Vec<String>) -> Self {
+        BackupParams {
+            ignored: Some(ignored_files.join("\n")).filter(|ignored| !ignored.is_empty()),
+            ..self
+        }
+    }
+
     /// Makes the backup locked. Please note that this requires extra permissions
     pub fn set_locked(self) -> Self {
         BackupParams {
@@ -78,6 +155,7 @@ impl From<Backup> for BackupParams {
         BackupParams {
             name: Some(value.name),
             is_locked: value.is_locked,
+            ..BackupParams::new().with_ignored_files(value.ignored_files)
         }
     }
 }
@@ -85,8 +163,22 @@ impl From<Backup> for BackupParams {
 impl Server<'_> {
     /// Gets the list of backups for this server
     pub async fn list_backups(&self) -> crate::Result<Vec<Backup>> {
+        self.list_backups_with_options(ListOptions::default()).await
+    }
+
+    /// Gets the list of backups for this server, filtered and sorted by the panel according to the
+    /// given options
+    pub async fn list_backups_with_options(
+        &self,
+        options: impl Into<ListOptions>,
+    ) -> crate::Result<Vec<Backup>> {
         self.client
-            .request::<PteroList<Backup>>(Method::GET, &format!("servers/{}/backups", self.id))
+            .request::<PteroList<Backup>>(
+                Method::GET,
+                &options
+                    .into()
+                    .apply_to(&format!("servers/{}/backups", self.id)),
+            )
             .await
             .map(|backups| backups.data)
     }
@@ -98,14 +190,15 @@ impl Server<'_> {
 
     /// Creates a backup with the given parameters
     ///
-    /// ```
+    /// ```no_run
     /// # use pterodactyl_api::client::backups::BackupParams;
     /// # use pterodactyl_api::client::Server;
     /// # let server: Server = todo!();
     /// # async {
     /// server.create_backup_with_params("Test Backup").await?;
     /// server.create_backup_with_params(BackupParams::new().with_name("Test Backup 2").set_locked()).await?;
-    /// # }
+    /// # Ok::<_, pterodactyl_api::Error>(())
+    /// # };
     /// ```
     pub async fn create_backup_with_params(
         &self,
@@ -132,6 +225,31 @@ impl Server<'_> {
             .map(|backup| backup.attributes)
     }
 
+    /// Polls the backup with the given ID every `poll_interval` until it has completed, then
+    /// returns it. Returns [`crate::Error::Timeout`] if it didn't complete within `timeout`, and
+    /// [`crate::Error::BackupFailed`] if it completed unsuccessfully
+    pub async fn wait_for_backup(
+        &self,
+        id: Uuid,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> crate::Result<Backup> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let backup = self.get_backup(id).await?;
+            if backup.completed_at.is_some() {
+                if !backup.is_successful {
+                    return Err(crate::Error::BackupFailed);
+                }
+                return Ok(backup);
+            }
+            if tokio::time::Instant::now() + poll_interval > deadline {
+                return Err(crate::Error::Timeout);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
     /// Gets a one-time use download link for a backup
     pub async fn get_backup_download_link(&self, id: Uuid) -> crate::Result<String> {
         #[derive(Deserialize)]
@@ -147,6 +265,87 @@ impl Server<'_> {
             .map(|url| url.attributes.url)
     }
 
+    /// Downloads the backup with the given ID as a `.tar.gz` to the given local path, returning the
+    /// number of bytes written. The backup is streamed to disk rather than loaded into memory. If
+    /// the `checksum` feature is enabled and the checksum algorithm of the backup is supported, the
+    /// download is verified against it, and the local file is deleted if it doesn't match. Returns
+    /// [`crate::Error::BackupFailed`] without downloading anything if the backup completed
+    /// unsuccessfully
+    #[cfg(feature = "stream")]
+    pub async fn download_backup_to_path(
+        &self,
+        id: Uuid,
+        local: impl AsRef<std::path::Path>,
+    ) -> crate::Result<u64> {
+        use futures_util::StreamExt;
+        use tokio::io::AsyncWriteExt;
+
+        let backup = self.get_backup(id).await?;
+        if backup.completed_at.is_some() && !backup.is_successful {
+            return Err(crate::Error::BackupFailed);
+        }
+        #[cfg(feature = "checksum")]
+        let mut hasher = backup.checksum.as_deref().and_then(ChecksumHasher::new);
+
+        // the download link points directly to Wings and is authenticated by a token in the URL,
+        // so it must not be sent through the panel request pipeline
+        let url = self.get_backup_download_link(id).await?;
+        let response = self.client.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(crate::Error::Http(response.status()));
+        }
+
+        let local = local.as_ref();
+        let mut file = tokio::fs::File::create(local).await?;
+        let mut stream = response.bytes_stream();
+        let mut written = 0;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            #[cfg(feature = "checksum")]
+            if let Some(hasher) = &mut hasher {
+                hasher.update(&chunk);
+            }
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        file.flush().await?;
+        drop(file);
+
+        #[cfg(feature = "checksum")]
+        if let Some(hasher) = hasher {
+            if !hasher.matches() {
+                tokio::fs::remove_file(local).await?;
+                return Err(crate::Error::ChecksumMismatch);
+            }
+        }
+
+        Ok(written)
+    }
+
+    /// Creates a backup with the given parameters, waits for it to complete using
+    /// [`Server::wait_for_backup`], and downloads it to the given local path using
+    /// [`Server::download_backup_to_path`], returning the number of bytes written. If
+    /// `delete_after` is `true`, the backup is then deleted from the panel, but only if the download
+    /// succeeded, so that a failed export never loses the backup
+    #[cfg(feature = "stream")]
+    pub async fn export_backup_to_path(
+        &self,
+        params: impl Into<BackupParams>,
+        local: impl AsRef<std::path::Path>,
+        delete_after: bool,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> crate::Result<u64> {
+        let backup = self.create_backup_with_params(params).await?;
+        self.wait_for_backup(backup.uuid, timeout, poll_interval)
+            .await?;
+        let written = self.download_backup_to_path(backup.uuid, local).await?;
+        if delete_after {
+            self.delete_backup(backup.uuid).await?;
+        }
+        Ok(written)
+    }
+
     /// Deletes the backup with the given ID
     pub async fn delete_backup(&self, id: Uuid) -> crate::Result<()> {
         self.client
@@ -158,3 +357,56 @@ impl Server<'_> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::BackupParams;
+
+    #[test]
+    fn test_backup_params_ignored_files() {
+        let params = BackupParams::new()
+            .with_name("Test")
+            .with_ignored_files(vec!["world/tmp".to_owned(), "*.log".to_owned()]);
+        assert_eq!(
+            serde_json::to_string(&params).unwrap(),
+            r#"{"name":"Test","ignored":"world/tmp\n*.log"}"#
+        );
+        let params = BackupParams::new().with_ignored_files(Vec::new());
+        assert_eq!(serde_json::to_string(&params).unwrap(), "{}");
+    }
+
+    #[cfg(feature = "checksum")]
+    #[test]
+    fn test_verify_checksum() {
+        use super::Backup;
+
+        let backup = |checksum: Option<&str>| Backup {
+            uuid: uuid::Uuid::nil(),
+            name: String::new(),
+            ignored_files: Vec::new(),
+            checksum: checksum.map(str::to_owned),
+            bytes: 0,
+            created_at: time::OffsetDateTime::UNIX_EPOCH,
+            completed_at: None,
+            is_successful: false,
+            is_locked: false,
+        };
+        assert_eq!(
+            backup(Some("sha1:a9993e364706816aba3e25717850c26c9cd0d89d")).verify_checksum(b"abc"),
+            Some(true)
+        );
+        assert_eq!(
+            backup(Some(
+                "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+            ))
+            .verify_checksum(b"abc"),
+            Some(true)
+        );
+        assert_eq!(
+            backup(Some("sha1:a9993e364706816aba3e25717850c26c9cd0d89d")).verify_checksum(b"abd"),
+            Some(false)
+        );
+        assert_eq!(backup(Some("md5:abc")).verify_checksum(b"abc"), None);
+        assert_eq!(backup(None).verify_checksum(b"abc"), None);
+    }
+}
@@ -1,11 +1,17 @@
 //! API for endpoints under `api/client/servers/{server}/files`
 
 use crate::client::Server;
-use crate::http::{EmptyBody, NullErrorHandler, RawBody};
+#[cfg(feature = "stream")]
+use crate::http::SizedBody;
+use crate::http::{EmptyBody, NullErrorHandler, RawBody, WithHeader};
 use crate::structs::{PteroList, PteroObject};
 use bytes::Bytes;
-use reqwest::{Body, Method};
-use serde::{Deserialize, Deserializer, Serialize};
+use reqwest::header::RANGE;
+use reqwest::{Body, Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Display, Formatter, Write};
+use std::time::Duration;
 use time::OffsetDateTime;
 
 fn split_dir_filename(file: &str) -> (&str, &str) {
@@ -60,8 +66,47 @@ fn relativize(file: &str, root: &str) -> String {
     result
 }
 
+fn join_dir(dir: &str) -> String {
+    let mut dir = dir.to_owned();
+    if !dir.is_empty() && !dir.ends_with('/') {
+        dir.push('/');
+    }
+    dir
+}
+
+fn glob_matches(pattern: &str, path: &str) -> bool {
+    fn segment_matches(pattern: &[char], segment: &[char]) -> bool {
+        match pattern.split_first() {
+            None => segment.is_empty(),
+            Some(('*', rest)) => (0..=segment.len()).any(|i| segment_matches(rest, &segment[i..])),
+            Some(('?', rest)) => !segment.is_empty() && segment_matches(rest, &segment[1..]),
+            Some((c, rest)) => segment.first() == Some(c) && segment_matches(rest, &segment[1..]),
+        }
+    }
+
+    fn parts_match(pattern: &[&str], path: &[&str]) -> bool {
+        match pattern.split_first() {
+            None => path.is_empty(),
+            Some((&"**", rest)) => (0..=path.len()).any(|i| parts_match(rest, &path[i..])),
+            Some((part, rest)) => {
+                !path.is_empty()
+                    && segment_matches(
+                        &part.chars().collect::<Vec<_>>(),
+                        &path[0].chars().collect::<Vec<_>>(),
+                    )
+                    && parts_match(rest, &path[1..])
+            }
+        }
+    }
+
+    parts_match(
+        &file_parts(pattern).collect::<Vec<_>>(),
+        &file_parts(path).collect::<Vec<_>>(),
+    )
+}
+
 /// Represents a file on the file system of a Pterodactyl server
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct PteroFile {
     /// The file name
@@ -89,11 +134,17 @@ pub struct PteroFile {
     pub mimetype: String,
 
     /// When the file was created
-    #[serde(deserialize_with = "crate::structs::iso_time")]
+    #[serde(
+        deserialize_with = "crate::structs::iso_time",
+        serialize_with = "crate::structs::serialize_iso_time"
+    )]
     pub created_at: OffsetDateTime,
 
     /// When the file was last modified
-    #[serde(deserialize_with = "crate::structs::iso_time")]
+    #[serde(
+        deserialize_with = "crate::structs::iso_time",
+        serialize_with = "crate::structs::serialize_iso_time"
+    )]
     pub modified_at: OffsetDateTime,
 }
 
@@ -205,6 +256,84 @@ impl<'de> Deserialize<'de> for PteroFilePermissions {
     }
 }
 
+impl PteroFilePermissions {
+    /// Converts these permissions into a numeric Unix mode, such as `0o755`. Only the permission
+    /// bits (including the setuid, setgid and sticky bits) are included, not the file type. The
+    /// setuid and sticky flags are only representable for the owner and other users respectively,
+    /// while the group owner's setuid flag represents the setgid bit.
+    pub fn to_mode(&self) -> u32 {
+        fn user_mode(user: &PteroUserFilePermissions) -> u32 {
+            (u32::from(user.read) << 2) | (u32::from(user.write) << 1) | u32::from(user.executable)
+        }
+        (u32::from(self.owner.setuid) << 11)
+            | (u32::from(self.group_owner.setuid) << 10)
+            | (u32::from(self.other_users.sticky) << 9)
+            | (user_mode(&self.owner) << 6)
+            | (user_mode(&self.group_owner) << 3)
+            | user_mode(&self.other_users)
+    }
+
+    /// Creates permissions from a numeric Unix mode, such as `0o755`. If the file type bits are
+    /// present, they are used to determine the file type, otherwise the file is assumed to be a
+    /// normal file.
+    pub fn from_mode(mode: u32) -> Self {
+        fn user_permissions(mode: u32, setuid: bool, sticky: bool) -> PteroUserFilePermissions {
+            PteroUserFilePermissions {
+                read: mode & 0o4 != 0,
+                write: mode & 0o2 != 0,
+                executable: mode & 0o1 != 0,
+                setuid,
+                sticky,
+            }
+        }
+        PteroFilePermissions {
+            file_type: match mode & 0o170000 {
+                0o040000 => PteroFileType::Directory,
+                0o120000 => PteroFileType::Symlink,
+                _ => PteroFileType::Normal,
+            },
+            owner: user_permissions(mode >> 6, mode & 0o4000 != 0, false),
+            group_owner: user_permissions(mode >> 3, mode & 0o2000 != 0, false),
+            other_users: user_permissions(mode, false, mode & 0o1000 != 0),
+        }
+    }
+}
+
+impl Display for PteroFilePermissions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fn write_user(f: &mut Formatter<'_>, user: &PteroUserFilePermissions) -> std::fmt::Result {
+            f.write_char(if user.read { 'r' } else { '-' })?;
+            f.write_char(if user.write { 'w' } else { '-' })?;
+            f.write_char(match (user.executable, user.setuid, user.sticky) {
+                (true, true, _) => 's',
+                (false, true, _) => 'S',
+                (true, false, true) => 't',
+                (false, false, true) => 'T',
+                (true, false, false) => 'x',
+                (false, false, false) => '-',
+            })
+        }
+
+        f.write_char(match self.file_type {
+            PteroFileType::Directory => 'd',
+            PteroFileType::Symlink => 'l',
+            PteroFileType::Normal => '-',
+        })?;
+        write_user(f, &self.owner)?;
+        write_user(f, &self.group_owner)?;
+        write_user(f, &self.other_users)
+    }
+}
+
+impl Serialize for PteroFilePermissions {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 impl Server<'_> {
     /// Lists the files in a directory on the server
     pub async fn list_files(&self, directory: impl AsRef<str>) -> crate::Result<Vec<PteroFile>> {
@@ -221,6 +350,120 @@ impl Server<'_> {
             .map(|files| files.data)
     }
 
+    /// Lists the files in a directory on the server whose names contain `query`, ignoring case. The
+    /// query is passed to the panel as a `search` parameter so that panels which support it can
+    /// filter server-side, but panels which ignore the parameter return the whole directory, so the
+    /// results are always filtered again locally. Unlike [`Server::glob`], this does not search
+    /// subdirectories
+    pub async fn search_files(
+        &self,
+        directory: impl AsRef<str>,
+        query: impl AsRef<str>,
+    ) -> crate::Result<Vec<PteroFile>> {
+        let query = query.as_ref();
+        let lowercase_query = query.to_lowercase();
+        self.client
+            .request::<PteroList<PteroFile>>(
+                Method::GET,
+                &format!(
+                    "servers/{}/files/list?directory={}&search={}",
+                    self.id,
+                    urlencoding::encode(directory.as_ref()),
+                    urlencoding::encode(query)
+                ),
+            )
+            .await
+            .map(|files| {
+                files
+                    .data
+                    .into_iter()
+                    .filter(|file| file.name.to_lowercase().contains(&lowercase_query))
+                    .collect()
+            })
+    }
+
+    /// Recursively walks the given directory on the server, returning the full paths of all the
+    /// files (and directories) for which `predicate` returns `true`. Symlinks are not followed, to
+    /// avoid cycles
+    pub async fn find_files(
+        &self,
+        root: impl AsRef<str>,
+        predicate: impl FnMut(&str, &PteroFile) -> bool,
+    ) -> crate::Result<Vec<(String, PteroFile)>> {
+        self.walk_files(root.as_ref(), None, predicate).await
+    }
+
+    /// Recursively lists all the files (and directories) under the given directory on the server,
+    /// depth-first, along with their full paths. Directories nested more than `max_depth` levels
+    /// below `root` are not listed, so a `max_depth` of `Some(0)` lists only `root` itself.
+    /// Symlinks are not followed, to avoid cycles
+    pub async fn list_files_recursive(
+        &self,
+        root: impl AsRef<str>,
+        max_depth: Option<usize>,
+    ) -> crate::Result<Vec<(String, PteroFile)>> {
+        self.walk_files(root.as_ref(), max_depth, |_, _| true).await
+    }
+
+    async fn walk_files(
+        &self,
+        root: &str,
+        max_depth: Option<usize>,
+        mut predicate: impl FnMut(&str, &PteroFile) -> bool,
+    ) -> crate::Result<Vec<(String, PteroFile)>> {
+        let mut result = Vec::new();
+        let mut dirs = vec![(join_dir(root), 0)];
+        while let Some((dir, depth)) = dirs.pop() {
+            for file in self.list_files(&dir).await? {
+                let path = format!("{}{}", dir, file.name);
+                if !file.is_file && !file.is_symlink && max_depth.is_none_or(|max| depth < max) {
+                    dirs.push((join_dir(&path), depth + 1));
+                }
+                if predicate(&path, &file) {
+                    result.push((path, file));
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Finds all files on the server matching the given glob pattern, returning their full paths.
+    /// `*` matches any sequence of characters within a path segment, `?` matches any single
+    /// character, and `**` matches any number of path segments. Symlinks are not followed
+    ///
+    /// ```no_run
+    /// # use pterodactyl_api::client::Server;
+    /// # let server: Server = todo!();
+    /// # async {
+    /// let logs = server.glob("/logs/**/*.log").await?;
+    /// # Ok::<_, pterodactyl_api::Error>(())
+    /// # };
+    /// ```
+    pub async fn glob(&self, pattern: impl AsRef<str>) -> crate::Result<Vec<(String, PteroFile)>> {
+        let pattern = pattern.as_ref();
+        let parts = file_parts(pattern).collect::<Vec<_>>();
+        // the last segment is always matched against a directory listing, even without wildcards,
+        // since it may name a file rather than a directory
+        let literal_parts = parts
+            .iter()
+            .take(parts.len().saturating_sub(1))
+            .take_while(|part| !part.contains(['*', '?']))
+            .count();
+        let mut root = "/".to_owned();
+        for part in &parts[..literal_parts] {
+            root.push_str(part);
+            root.push('/');
+        }
+        let remaining = &parts[literal_parts..];
+        let max_depth = if remaining.contains(&"**") {
+            None
+        } else {
+            Some(remaining.len().saturating_sub(1))
+        };
+        self.walk_files(&root, max_depth, |path, _| glob_matches(pattern, path))
+            .await
+    }
+
     /// Gets the file contents of a file on the server, as a [`Bytes`]
     pub async fn file_contents(&self, file: impl AsRef<str>) -> crate::Result<Bytes> {
         Ok(self
@@ -239,9 +482,21 @@ impl Server<'_> {
             .await?)
     }
 
-    /// Gets the file contents of a UTF8-encoded file on the server, as a [`String`]
-    pub async fn file_contents_text(&self, file: impl AsRef<str>) -> crate::Result<String> {
-        Ok(self
+    /// Gets part of the file contents of a file on the server, from the byte offset `start` up to
+    /// and including the byte offset `end`, or to the end of the file if `end` is `None`. This
+    /// sends a `Range` header, so that only the requested part is downloaded if Wings supports
+    /// ranges. Otherwise, the whole file is downloaded and the requested part is returned
+    pub async fn file_contents_range(
+        &self,
+        file: impl AsRef<str>,
+        start: u64,
+        end: Option<u64>,
+    ) -> crate::Result<Bytes> {
+        let range = match end {
+            Some(end) => format!("bytes={start}-{end}"),
+            None => format!("bytes={start}-"),
+        };
+        let response = self
             .client
             .get_response::<_, NullErrorHandler>(
                 Method::GET,
@@ -250,11 +505,44 @@ impl Server<'_> {
                     self.id,
                     urlencoding::encode(file.as_ref())
                 ),
-                EmptyBody,
+                WithHeader(EmptyBody, RANGE, range),
             )
-            .await?
-            .text()
-            .await?)
+            .await?;
+        let partial = response.status() == StatusCode::PARTIAL_CONTENT;
+        let contents = response.bytes().await?;
+        if partial {
+            return Ok(contents);
+        }
+        let len = contents.len();
+        let start = usize::try_from(start).unwrap_or(usize::MAX).min(len);
+        let end = end
+            .and_then(|end| usize::try_from(end).ok())
+            .map_or(len, |end| end.saturating_add(1).min(len))
+            .max(start);
+        Ok(contents.slice(start..end))
+    }
+
+    /// Gets the file contents of a UTF8-encoded file on the server, as a [`String`]. Returns
+    /// [`crate::Error::InvalidUtf8`] if the file is not valid UTF-8, in which case
+    /// [`Server::file_contents`] or [`Server::file_contents_text_lossy`] can be used instead
+    pub async fn file_contents_text(&self, file: impl AsRef<str>) -> crate::Result<String> {
+        let contents = self.file_contents(file).await?;
+        Ok(String::from_utf8(contents.into())?)
+    }
+
+    /// Gets the file contents of a file on the server as a [`String`], replacing any invalid UTF-8
+    /// sequences with the replacement character `U+FFFD`
+    pub async fn file_contents_text_lossy(&self, file: impl AsRef<str>) -> crate::Result<String> {
+        let contents = self.file_contents(file).await?;
+        Ok(String::from_utf8_lossy(&contents).into_owned())
+    }
+
+    /// Gets the file contents of a JSON file on the server, deserialized into the given type
+    pub async fn file_contents_json<T: DeserializeOwned>(
+        &self,
+        file: impl AsRef<str>,
+    ) -> crate::Result<T> {
+        Ok(serde_json::from_slice(&self.file_contents(file).await?)?)
     }
 
     /// Streams the file contents of a  file on the server, as an async stream
@@ -278,6 +566,48 @@ impl Server<'_> {
             .bytes_stream())
     }
 
+    /// Gets the file contents of a file on the server, as a [`Bytes`], calling `progress` after each
+    /// chunk is received with the number of bytes received so far and the total size of the file,
+    /// if known
+    #[cfg(feature = "stream")]
+    pub async fn download_file_with_progress(
+        &self,
+        file: impl AsRef<str>,
+        mut progress: impl FnMut(u64, Option<u64>),
+    ) -> crate::Result<Bytes> {
+        use futures_util::StreamExt;
+
+        let response = self
+            .client
+            .get_response::<_, NullErrorHandler>(
+                Method::GET,
+                &format!(
+                    "servers/{}/files/contents?file={}",
+                    self.id,
+                    urlencoding::encode(file.as_ref())
+                ),
+                EmptyBody,
+            )
+            .await?;
+        let total = response.content_length();
+        // the Content-Length comes from the panel, so don't trust it with a large allocation
+        // up front, and let the buffer grow as chunks arrive instead
+        const MAX_PREALLOCATION: usize = 8 * 1024 * 1024;
+        let mut contents = Vec::with_capacity(
+            total
+                .and_then(|total| usize::try_from(total).ok())
+                .unwrap_or(0)
+                .min(MAX_PREALLOCATION),
+        );
+        let mut stream = response.bytes_stream();
+        progress(0, total);
+        while let Some(chunk) = stream.next().await {
+            contents.extend_from_slice(&chunk?);
+            progress(contents.len() as u64, total);
+        }
+        Ok(contents.into())
+    }
+
     /// Gets a one-time download URL for a file on the server
     pub async fn get_file_download_url(&self, file: impl AsRef<str>) -> crate::Result<String> {
         #[derive(Deserialize)]
@@ -306,6 +636,46 @@ impl Server<'_> {
         self.rename_files(vec![(from.into(), to.into())]).await
     }
 
+    /// Renames or moves a file on the server, first checking whether the destination already
+    /// exists. If it does, returns [`crate::Error::FileExists`], unless `overwrite` is `true` in
+    /// which case the existing destination is replaced. To replace it, the existing destination is
+    /// first moved out of the way, and only deleted once the rename has succeeded. If the rename
+    /// fails, the existing destination is moved back
+    pub async fn rename_file_checked(
+        &self,
+        from: impl Into<String>,
+        to: impl Into<String>,
+        overwrite: bool,
+    ) -> crate::Result<()> {
+        let to = to.into();
+        let (dir, filename) = split_dir_filename(&to);
+        let exists = self
+            .list_files(if dir.is_empty() { "/" } else { dir })
+            .await?
+            .iter()
+            .any(|file| file.name == filename);
+        if !exists {
+            return self.rename_file(from, to).await;
+        }
+        if !overwrite {
+            return Err(crate::Error::FileExists);
+        }
+
+        // Wings refuses to rename over an existing file
+        let backup = format!(
+            "{}.{}.old",
+            to,
+            OffsetDateTime::now_utc().unix_timestamp_nanos()
+        );
+        self.rename_file(to.clone(), backup.clone()).await?;
+        if let Err(err) = self.rename_file(from, to.clone()).await {
+            // if moving the destination back fails too, it is still available at the backup path
+            let _ = self.rename_file(backup, to).await;
+            return Err(err);
+        }
+        self.delete_file(backup).await
+    }
+
     /// Bulk renames or moves files on this server. Takes a set of pairs, the first element is the
     /// file to rename from, and the second element is what to rename into.
     pub async fn rename_files(&self, files: Vec<(String, String)>) -> crate::Result<()> {
@@ -385,11 +755,25 @@ impl Server<'_> {
         Ok(())
     }
 
-    /// Overwrites the given file on this server with the given data
+    /// Overwrites the given file on this server with the given data, sent as
+    /// `application/octet-stream`. If `data` is a streaming [`Body`] of unknown length, it is sent
+    /// using `Transfer-Encoding: chunked`, see [`Server::write_file_stream`]
     pub async fn write_file(
         &self,
         file: impl AsRef<str>,
         data: impl Into<Body>,
+    ) -> crate::Result<()> {
+        self.write_file_with_content_type(file, data, "application/octet-stream")
+            .await
+    }
+
+    /// Overwrites the given file on this server with the given data, sent with the given content
+    /// type, such as `text/plain`
+    pub async fn write_file_with_content_type(
+        &self,
+        file: impl AsRef<str>,
+        data: impl Into<Body>,
+        content_type: impl AsRef<str>,
     ) -> crate::Result<()> {
         self.client
             .request_with_body::<EmptyBody, _>(
@@ -399,12 +783,90 @@ impl Server<'_> {
                     self.id,
                     urlencoding::encode(file.as_ref())
                 ),
-                RawBody(data),
+                RawBody(data, content_type.as_ref()),
             )
             .await?;
         Ok(())
     }
 
+    /// Overwrites the given file on this server with the contents of the given local file. The
+    /// local file is streamed rather than loaded into memory, making this suitable for large files
+    #[cfg(feature = "stream")]
+    pub async fn write_file_from_path(
+        &self,
+        file: impl AsRef<str>,
+        local: impl AsRef<std::path::Path>,
+    ) -> crate::Result<()> {
+        let local = tokio::fs::File::open(local).await?;
+        let length = local.metadata().await?.len();
+        self.client
+            .request_with_body::<EmptyBody, _>(
+                Method::POST,
+                &format!(
+                    "servers/{}/files/write?file={}",
+                    self.id,
+                    urlencoding::encode(file.as_ref())
+                ),
+                SizedBody(local, length),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Overwrites the given file on this server with the chunks of the given stream, sent as
+    /// `application/octet-stream`. Since the length of the stream isn't known in advance, it is
+    /// sent using `Transfer-Encoding: chunked`, so large files can be uploaded without knowing
+    /// their size upfront or loading them into memory. The upload is aborted if the stream yields
+    /// an error.
+    ///
+    /// Note that the panel receives the whole file before forwarding it to Wings, so its web
+    /// server's maximum request body size still applies
+    #[cfg(feature = "stream")]
+    pub async fn write_file_stream(
+        &self,
+        file: impl AsRef<str>,
+        stream: impl futures_core::Stream<Item = std::io::Result<Bytes>> + Send + 'static,
+    ) -> crate::Result<()> {
+        self.write_file(file, Body::wrap_stream(stream)).await
+    }
+
+    /// Overwrites the given file on this server with the data read from the given reader until it
+    /// reaches the end. The data is streamed rather than loaded into memory, see
+    /// [`Server::write_file_stream`]
+    #[cfg(feature = "stream")]
+    pub async fn write_file_from_reader(
+        &self,
+        file: impl AsRef<str>,
+        reader: impl tokio::io::AsyncRead + Send + 'static,
+    ) -> crate::Result<()> {
+        use tokio::io::AsyncReadExt;
+
+        const CHUNK_SIZE: usize = 64 * 1024;
+
+        let stream = futures_util::stream::unfold(Some(Box::pin(reader)), |reader| async move {
+            let mut reader = reader?;
+            let mut chunk = bytes::BytesMut::with_capacity(CHUNK_SIZE);
+            match reader.read_buf(&mut chunk).await {
+                Ok(0) => None,
+                Ok(_) => Some((Ok(chunk.freeze()), Some(reader))),
+                // end the stream after an error
+                Err(err) => Some((Err(err), None)),
+            }
+        });
+        self.write_file_stream(file, stream).await
+    }
+
+    /// Overwrites the given file on this server with the given value, serialized as pretty-printed
+    /// JSON
+    pub async fn write_file_json<T: Serialize + ?Sized>(
+        &self,
+        file: impl AsRef<str>,
+        value: &T,
+    ) -> crate::Result<()> {
+        self.write_file(file, serde_json::to_vec_pretty(value)?)
+            .await
+    }
+
     /// Compresses a file (or directory) on this server into a tarball (`.tar.gz`)
     pub async fn compress_file(&self, file: impl Into<String>) -> crate::Result<PteroFile> {
         let file = file.into();
@@ -441,24 +903,102 @@ impl Server<'_> {
         &self,
         file: impl Into<String>,
         dest: impl Into<String>,
+    ) -> crate::Result<()> {
+        let dest = dest.into();
+        let file = relativize(&file.into(), &dest);
+        self.decompress_file_relative(file, dest).await
+    }
+
+    /// Decompresses a tarball (`.tar.gz`) into the specified destination directory on this server,
+    /// where the path of the tarball is already relative to the destination directory
+    pub async fn decompress_file_relative(
+        &self,
+        file: impl Into<String>,
+        dest: impl Into<String>,
     ) -> crate::Result<()> {
         #[derive(Serialize)]
         struct DecompressBody {
             root: String,
             file: String,
         }
-        let dest = dest.into();
-        let file = relativize(&file.into(), &dest);
         self.client
             .request_with_body::<EmptyBody, _>(
                 Method::POST,
                 &format!("servers/{}/files/decompress", self.id),
-                &DecompressBody { root: dest, file },
+                &DecompressBody {
+                    root: dest.into(),
+                    file: file.into(),
+                },
             )
             .await?;
         Ok(())
     }
 
+    /// Decompresses a tarball (`.tar.gz`) into the specified destination directory on this server,
+    /// then lists the files in the destination directory, so that the extraction can be verified.
+    /// Since Wings does not report which files were extracted, this includes any files which were
+    /// already in the destination directory
+    pub async fn decompress_file_and_list(
+        &self,
+        file: impl Into<String>,
+        dest: impl Into<String>,
+    ) -> crate::Result<Vec<PteroFile>> {
+        let dest = dest.into();
+        self.decompress_file(file, dest.clone()).await?;
+        self.list_files(dest).await
+    }
+
+    /// Decompresses a tarball (`.tar.gz`) into the specified destination directory on this server,
+    /// then waits until the extraction appears to be complete. Since Wings may keep extracting in
+    /// the background and does not signal when it's done, the destination directory is listed
+    /// recursively before decompressing and then every `poll_interval`. The extraction is
+    /// considered complete once the number, total size and latest modification time of the files
+    /// in it have changed from before decompressing, and are then unchanged between two listings.
+    /// Returns [`crate::Error::Timeout`] if this didn't happen within `timeout`, which is also the
+    /// case for an empty archive.
+    ///
+    /// Each poll lists the whole destination recursively, which takes one request per directory,
+    /// so prefer a `dest` which doesn't already contain a large tree, and a `poll_interval`
+    /// proportionate to its size
+    pub async fn decompress_file_and_wait(
+        &self,
+        file: impl Into<String>,
+        dest: impl Into<String>,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> crate::Result<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let dest = dest.into();
+        let snapshot = |files: Vec<(String, PteroFile)>| {
+            (
+                files.len(),
+                files
+                    .iter()
+                    .filter(|(_, file)| file.is_file)
+                    .map(|(_, file)| file.size)
+                    .sum::<u64>(),
+                files.iter().map(|(_, file)| file.modified_at).max(),
+            )
+        };
+        let before = snapshot(self.list_files_recursive(&dest, None).await?);
+        self.decompress_file(file, dest.clone()).await?;
+        let mut previous = None;
+        loop {
+            let current = snapshot(self.list_files_recursive(&dest, None).await?);
+            // until the destination changes, the extraction may not have started yet
+            if current != before {
+                if previous == Some(current) {
+                    return Ok(());
+                }
+                previous = Some(current);
+            }
+            if tokio::time::Instant::now() + poll_interval > deadline {
+                return Err(crate::Error::Timeout);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
     /// Deletes the given file or directory on this server
     pub async fn delete_file(&self, file: impl Into<String>) -> crate::Result<()> {
         self.delete_files(vec![file.into()]).await
@@ -481,6 +1021,53 @@ impl Server<'_> {
         Ok(())
     }
 
+    /// Deletes all the files and directories inside the given directory on this server, without
+    /// deleting the directory itself. No deletion request is made if the directory is empty
+    pub async fn delete_directory_contents(&self, dir: impl AsRef<str>) -> crate::Result<()> {
+        let dir = join_dir(dir.as_ref());
+        let files: Vec<_> = self
+            .list_files(&dir)
+            .await?
+            .into_iter()
+            .map(|file| format!("{}{}", dir, file.name))
+            .collect();
+        if files.is_empty() {
+            return Ok(());
+        }
+        self.delete_files(files).await
+    }
+
+    /// Recursively deletes all the files and directories inside the given directory on this server
+    /// for which `predicate` returns `true`, in a single bulk deletion. See [`Server::find_files`].
+    /// No deletion request is made if nothing matches
+    pub async fn delete_all_matching(
+        &self,
+        dir: impl AsRef<str>,
+        predicate: impl FnMut(&str, &PteroFile) -> bool,
+    ) -> crate::Result<()> {
+        let paths: Vec<_> = self
+            .find_files(dir, predicate)
+            .await?
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        // directories are deleted recursively, so don't also delete their contents
+        let files: Vec<_> = paths
+            .iter()
+            .filter(|file| {
+                !paths.iter().any(|dir| {
+                    file.strip_prefix(dir.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+                })
+            })
+            .cloned()
+            .collect();
+        if files.is_empty() {
+            return Ok(());
+        }
+        self.delete_files(files).await
+    }
+
     /// Creates a folder on this server
     pub async fn create_folder(&self, folder: impl Into<String>) -> crate::Result<()> {
         #[derive(Serialize)]
@@ -507,6 +1094,29 @@ impl Server<'_> {
         Ok(())
     }
 
+    /// Creates a folder on the server if it doesn't already exist. Unlike
+    /// [`Server::create_folder`], an existing folder is treated as success, making this suitable
+    /// for scripts which may be run multiple times. Returns [`crate::Error::FileExists`] if a file
+    /// which is not a folder exists at the given path
+    pub async fn create_folder_idempotent(&self, folder: impl Into<String>) -> crate::Result<()> {
+        let folder = folder.into();
+        let (dir, folder_name) = split_dir_filename(folder.trim_end_matches('/'));
+        let existing = match self
+            .list_files(if dir.is_empty() { "/" } else { dir })
+            .await
+        {
+            Ok(files) => files.into_iter().find(|file| file.name == folder_name),
+            // the parent doesn't exist yet, and will be created along with the folder
+            Err(crate::Error::ResourceNotFound { .. }) => None,
+            Err(err) => return Err(err),
+        };
+        match existing {
+            Some(file) if file.is_file => Err(crate::Error::FileExists),
+            Some(_) => Ok(()),
+            None => self.create_folder(folder).await,
+        }
+    }
+
     /// Gets a temporary upload URL to upload files
     pub async fn get_files_upload_url(&self) -> crate::Result<String> {
         #[derive(Deserialize)]
@@ -518,4 +1128,226 @@ impl Server<'_> {
             .await
             .map(|url| url.attributes.url)
     }
+
+    /// Uploads a file with the given name and contents into the given directory on the server,
+    /// overwriting any existing file with that name
+    pub async fn upload_file(
+        &self,
+        directory: impl AsRef<str>,
+        file_name: impl Into<String>,
+        data: impl Into<Bytes>,
+    ) -> crate::Result<()> {
+        // the upload URL points directly to Wings and is authenticated by a token in the URL, so
+        // it must not be sent through the panel request pipeline
+        let url = self.get_files_upload_url().await?;
+        let separator = if url.contains('?') { '&' } else { '?' };
+        let url = format!(
+            "{}{}directory={}",
+            url,
+            separator,
+            urlencoding::encode(directory.as_ref())
+        );
+        let data = data.into();
+        let length = data.len() as u64;
+        let part = reqwest::multipart::Part::stream_with_length(Body::from(data), length)
+            .file_name(file_name.into());
+        let response = self
+            .client
+            .client
+            .post(url)
+            .multipart(reqwest::multipart::Form::new().part("files", part))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(crate::Error::Http(response.status()));
+        }
+        Ok(())
+    }
+
+    /// Uploads a file as in [`Server::upload_file`], then verifies that the file is present in the
+    /// directory with the expected size, returning it. Returns
+    /// [`crate::Error::UploadVerificationFailed`] if it is not
+    pub async fn upload_file_and_verify(
+        &self,
+        directory: impl AsRef<str>,
+        file_name: impl Into<String>,
+        data: impl Into<Bytes>,
+    ) -> crate::Result<PteroFile> {
+        let directory = directory.as_ref();
+        let file_name = file_name.into();
+        let data = data.into();
+        let size = data.len() as u64;
+        self.upload_file(directory, file_name.clone(), data).await?;
+        self.list_files(directory)
+            .await?
+            .into_iter()
+            .find(|file| file.is_file && file.name == file_name && file.size == size)
+            .ok_or(crate::Error::UploadVerificationFailed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::client::files::{glob_matches, PteroFilePermissions, PteroFileType};
+    use serde::de::value::{Error, StrDeserializer};
+    use serde::Deserialize;
+    use std::time::Duration;
+
+    #[test]
+    fn test_permissions_mode_round_trip() {
+        for (string, mode) in [
+            ("-rwxr-xr-x", 0o755),
+            ("-rw-r--r--", 0o644),
+            ("-rwsr-sr-t", 0o7755),
+            ("-rwSr-Sr-T", 0o7644),
+            ("----------", 0o000),
+        ] {
+            let permissions =
+                PteroFilePermissions::deserialize(StrDeserializer::<Error>::new(string)).unwrap();
+            assert_eq!(permissions.to_mode(), mode);
+            assert_eq!(PteroFilePermissions::from_mode(mode), permissions);
+            assert_eq!(permissions.to_string(), string);
+        }
+
+        let directory = PteroFilePermissions::from_mode(0o040755);
+        assert_eq!(directory.file_type, PteroFileType::Directory);
+        assert_eq!(directory.to_string(), "drwxr-xr-x");
+        assert_eq!(directory.to_mode(), 0o755);
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("/logs/*.log", "/logs/latest.log"));
+        assert!(!glob_matches("/logs/*.log", "/logs/old/latest.log"));
+        assert!(glob_matches("/logs/**/*.log", "/logs/latest.log"));
+        assert!(glob_matches("/logs/**/*.log", "/logs/old/2024-01-01.log"));
+        assert!(glob_matches("**/*.jar", "/plugins/a.jar"));
+        assert!(glob_matches("world?/level.dat", "/world2/level.dat"));
+        assert!(!glob_matches("world?/level.dat", "/world/level.dat"));
+    }
+
+    /// A mock response listing the given files, with `None` as the size of directories
+    fn listing(files: &[(&str, Option<u64>)]) -> (reqwest::StatusCode, String) {
+        let data = files
+            .iter()
+            .map(|(name, size)| {
+                serde_json::json!({"object": "file_object", "attributes": {
+                    "name": name,
+                    "mode": if size.is_some() { "-rw-r--r--" } else { "drwxr-xr-x" },
+                    "size": size.unwrap_or(4096),
+                    "is_file": size.is_some(),
+                    "is_symlink": false,
+                    "mimetype": if size.is_some() { "text/plain" } else { "inode/directory" },
+                    "created_at": "2024-01-01T00:00:00+00:00",
+                    "modified_at": "2024-01-01T00:00:00+00:00",
+                }})
+            })
+            .collect::<Vec<_>>();
+        (
+            reqwest::StatusCode::OK,
+            serde_json::json!({"object": "list", "data": data}).to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_glob_lists_only_needed_directories() {
+        // a pattern without wildcards lists the parent directory instead of the file itself
+        let (url, server) =
+            crate::http::mock_server(vec![listing(&[("app.yml", Some(0)), ("other", None)])]).await;
+        let files = crate::client::ClientBuilder::new(url, "test-key")
+            .build()
+            .get_server("1")
+            .glob("config/app.yml")
+            .await
+            .unwrap();
+        assert_eq!(
+            files
+                .iter()
+                .map(|(path, _)| path.as_str())
+                .collect::<Vec<_>>(),
+            ["/config/app.yml"]
+        );
+        let requests = server.await.unwrap();
+        assert!(
+            requests[0].starts_with("GET /api/client/servers/1/files/list?directory=%2Fconfig%2F ")
+        );
+
+        // a pattern without ** doesn't descend further than it needs to
+        let (url, server) = crate::http::mock_server(vec![
+            listing(&[("a", None), ("b.log", Some(0))]),
+            listing(&[("latest.log", Some(0)), ("old", None)]),
+        ])
+        .await;
+        let files = crate::client::ClientBuilder::new(url, "test-key")
+            .build()
+            .get_server("1")
+            .glob("/logs/*/*.log")
+            .await
+            .unwrap();
+        assert_eq!(
+            files
+                .iter()
+                .map(|(path, _)| path.as_str())
+                .collect::<Vec<_>>(),
+            ["/logs/a/latest.log"]
+        );
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1]
+            .starts_with("GET /api/client/servers/1/files/list?directory=%2Flogs%2Fa%2F "));
+    }
+
+    #[tokio::test]
+    async fn test_decompress_file_and_wait() {
+        let (url, server) = crate::http::mock_server(vec![
+            listing(&[("old.txt", Some(3))]),
+            (reqwest::StatusCode::NO_CONTENT, String::new()),
+            // the extraction hasn't started yet, which isn't mistaken for a finished extraction
+            listing(&[("old.txt", Some(3))]),
+            listing(&[("old.txt", Some(3)), ("new.txt", Some(5))]),
+            listing(&[("old.txt", Some(3)), ("new.txt", Some(10))]),
+            listing(&[("old.txt", Some(3)), ("new.txt", Some(10))]),
+        ])
+        .await;
+        crate::client::ClientBuilder::new(url, "test-key")
+            .build()
+            .get_server("1")
+            .decompress_file_and_wait(
+                "/archive.tar.gz",
+                "/dest",
+                Duration::from_secs(10),
+                Duration::from_millis(1),
+            )
+            .await
+            .unwrap();
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 6);
+        assert!(
+            requests[0].starts_with("GET /api/client/servers/1/files/list?directory=%2Fdest%2F ")
+        );
+        assert!(requests[1].starts_with("POST /api/client/servers/1/files/decompress "));
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_write_file_from_reader() {
+        let (url, server) =
+            crate::http::mock_server(vec![(reqwest::StatusCode::NO_CONTENT, String::new())]).await;
+
+        crate::client::ClientBuilder::new(url, "test-key")
+            .build()
+            .get_server("1")
+            .write_file_from_reader("/large file.bin", &b"hello world"[..])
+            .await
+            .unwrap();
+        let request = &server.await.unwrap()[0];
+        assert!(
+            request.starts_with("POST /api/client/servers/1/files/write?file=%2Flarge%20file.bin")
+        );
+        let request = request.to_ascii_lowercase();
+        assert!(request.contains("transfer-encoding: chunked"));
+        assert!(request.contains("content-type: application/octet-stream"));
+        assert!(!request.contains("content-length"));
+        assert!(request.contains("hello world"));
+    }
 }
@@ -1,12 +1,18 @@
 //! Pterodactyl Client API implementation, for all endpoints under `api/client`
 
-use crate::http::EmptyBody;
+use crate::http::{EmptyBody, ErrorHandler, NullErrorHandler, RawBody};
 use crate::structs::{PteroList, PteroObject};
-use reqwest::Method;
+use futures_util::StreamExt;
+use reqwest::{Body, Method, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 pub use structs::*;
+use tokio::sync::Semaphore;
+use uuid::Uuid;
 
 pub mod account;
 pub mod backups;
@@ -22,7 +28,7 @@ pub mod users;
 pub mod websocket;
 
 /// The rate limits of the API key
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub struct RateLimits {
     /// The request limit per minute
     pub limit: u32,
@@ -30,28 +36,186 @@ pub struct RateLimits {
     pub limit_remaining: u32,
 }
 
-/// A Pterodactyl client, to make requests to the Pterodactyl client API
-#[derive(Debug)]
+/// Options for list endpoints, which are passed to the panel as query parameters so that filtering
+/// and sorting happens server-side. Which fields can be filtered and sorted by depends on the
+/// endpoint
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone)]
+pub struct ListOptions {
+    filters: Vec<(String, String)>,
+    sort: Option<String>,
+    includes: Vec<String>,
+}
+
+impl ListOptions {
+    /// Creates the default list options, which don't filter or sort
+    pub fn new() -> Self {
+        ListOptions::default()
+    }
+
+    /// Only lists objects whose `field` matches `value`, passed as `filter[field]=value`. Can be
+    /// called multiple times to filter by multiple fields
+    pub fn with_filter(mut self, field: impl Into<String>, value: impl Into<String>) -> Self {
+        self.filters.push((field.into(), value.into()));
+        self
+    }
+
+    /// Sorts the list by the given field, passed as `sort=field`. Prefix the field with `-` to sort
+    /// in descending order
+    pub fn with_sort(self, field: impl Into<String>) -> Self {
+        ListOptions {
+            sort: Some(field.into()),
+            ..self
+        }
+    }
+
+    /// Includes the given relationship in each listed object, passed as `include=relationship`.
+    /// Can be called multiple times to include multiple relationships
+    pub fn with_include(mut self, relationship: impl Into<String>) -> Self {
+        self.includes.push(relationship.into());
+        self
+    }
+
+    pub(crate) fn apply_to(&self, endpoint: &str) -> String {
+        let mut params = self
+            .filters
+            .iter()
+            .map(|(field, value)| {
+                format!(
+                    "filter%5B{}%5D={}",
+                    urlencoding::encode(field),
+                    urlencoding::encode(value)
+                )
+            })
+            .collect::<Vec<_>>();
+        if let Some(sort) = &self.sort {
+            params.push(format!("sort={}", urlencoding::encode(sort)));
+        }
+        if !self.includes.is_empty() {
+            params.push(format!(
+                "include={}",
+                urlencoding::encode(&self.includes.join(","))
+            ));
+        }
+        if params.is_empty() {
+            return endpoint.to_owned();
+        }
+        let separator = if endpoint.contains('?') { '&' } else { '?' };
+        format!("{}{}{}", endpoint, separator, params.join("&"))
+    }
+}
+
+/// A Pterodactyl client, to make requests to the Pterodactyl client API.
+///
+/// Clients are cheap to clone, and clones share the same rate limit information and cache
+#[derive(Debug, Clone)]
 pub struct Client {
     pub(crate) url: String,
     pub(crate) client: reqwest::Client,
-    pub(crate) api_key: String,
-    pub(crate) rate_limits: RwLock<Option<RateLimits>>,
+    // None if the underlying client handles authentication itself
+    pub(crate) api_key: Option<String>,
+    pub(crate) rate_limits: Arc<RwLock<Option<RateLimits>>>,
+    pub(crate) etag_cache: Option<Arc<RwLock<HashMap<String, CachedResponse>>>>,
+    pub(crate) concurrency_limit: Option<Arc<Semaphore>>,
+    pub(crate) on_request_complete: Option<RequestCompleteCallback>,
+}
+
+#[derive(Debug)]
+pub(crate) struct CachedResponse {
+    pub(crate) etag: reqwest::header::HeaderValue,
+    pub(crate) headers: reqwest::header::HeaderMap,
+    pub(crate) body: bytes::Bytes,
+}
+
+type RequestCompleteFn = dyn Fn(&str, StatusCode, Duration) + Send + Sync;
+
+#[derive(Clone)]
+pub(crate) struct RequestCompleteCallback(pub(crate) Arc<RequestCompleteFn>);
+
+impl std::fmt::Debug for RequestCompleteCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RequestCompleteCallback")
+    }
 }
 
 impl Client {
-    /// Gets the rate limit information after the previous request
+    /// Gets the rate limit information after the previous request, including requests which failed
+    /// with an error response such as [`crate::Error::RateLimit`]
     pub fn get_rate_limits(&self) -> Option<RateLimits> {
         *self.rate_limits.read().unwrap()
     }
 
     /// Lists the servers that this account has access to
     pub async fn list_servers(&self) -> crate::Result<Vec<ServerStruct>> {
-        self.request::<PteroList<ServerStruct>>(Method::GET, "")
+        self.list_servers_with_options(ListOptions::default()).await
+    }
+
+    /// Lists the servers that this account has access to, filtered and sorted by the panel
+    /// according to the given options
+    ///
+    /// ```no_run
+    /// # use pterodactyl_api::client::{Client, ListOptions};
+    /// # let client: Client = todo!();
+    /// # async {
+    /// let servers = client
+    ///     .list_servers_with_options(ListOptions::new().with_filter("name", "survival"))
+    ///     .await?;
+    /// # Ok::<_, pterodactyl_api::Error>(())
+    /// # };
+    /// ```
+    pub async fn list_servers_with_options(
+        &self,
+        options: impl Into<ListOptions>,
+    ) -> crate::Result<Vec<ServerStruct>> {
+        self.request::<PteroList<ServerStruct>>(Method::GET, &options.into().apply_to(""))
             .await
             .map(|servers| servers.data)
     }
 
+    /// Lists the servers that this account owns, excluding those it only has subuser access to
+    pub async fn list_servers_owned(&self) -> crate::Result<Vec<ServerStruct>> {
+        self.list_servers_of_type("owner").await
+    }
+
+    /// Lists every server on the panel, including those this account neither owns nor is a subuser
+    /// of. Requires this account to be an administrator
+    pub async fn list_servers_all_admin(&self) -> crate::Result<Vec<ServerStruct>> {
+        self.list_servers_of_type("admin-all").await
+    }
+
+    async fn list_servers_of_type(&self, ty: &str) -> crate::Result<Vec<ServerStruct>> {
+        self.request::<PteroList<ServerStruct>>(Method::GET, &format!("?type={}", ty))
+            .await
+            .map(|servers| servers.data)
+    }
+
+    /// Gets the resources of every server accessible to this client, keyed by server identifier.
+    /// Up to 8 requests are made concurrently, or up to 64 if a concurrency limit was set with
+    /// [`ClientBuilder::with_concurrency_limit`], in which case that limit applies as for any other
+    /// requests. A failure to get the resources of one server does not prevent the resources of
+    /// the other servers from being returned
+    pub async fn get_all_resources(
+        &self,
+    ) -> crate::Result<Vec<(String, crate::Result<ServerResources>)>> {
+        const DEFAULT_CONCURRENCY: usize = 8;
+        // the semaphore of the concurrency limit bounds the requests actually in flight
+        const LIMITED_CONCURRENCY: usize = 64;
+
+        let servers = self.list_servers().await?;
+        let concurrency = if self.concurrency_limit.is_some() {
+            LIMITED_CONCURRENCY
+        } else {
+            DEFAULT_CONCURRENCY
+        };
+        Ok(futures_util::stream::iter(servers)
+            .map(|server| async move {
+                let resources = self.get_server(&server.identifier).get_resources().await;
+                (server.identifier, resources)
+            })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await)
+    }
+
     /// Gets all available permissions on this instance of Pterodactyl
     pub async fn get_permissions(&self) -> crate::Result<HashMap<String, PermissionGroup>> {
         #[derive(Deserialize)]
@@ -63,7 +227,57 @@ impl Client {
             .map(|permissions| permissions.attributes.permissions)
     }
 
-    /// Gets a server with a specific ID, which can be used to make requests specific to that server
+    /// Gets all available permissions on this instance of Pterodactyl as typed permissions, mapped
+    /// to their descriptions. Permissions not known to this crate are returned as
+    /// [`Permission::Unknown`]
+    pub async fn get_permissions_typed(&self) -> crate::Result<HashMap<Permission, String>> {
+        Ok(self
+            .get_permissions()
+            .await?
+            .into_iter()
+            .flat_map(|(group_name, group)| {
+                group.keys.into_iter().map(move |(key, description)| {
+                    (Permission::from(format!("{group_name}.{key}")), description)
+                })
+            })
+            .collect())
+    }
+
+    /// Makes a request to an arbitrary endpoint relative to the client API URL, returning the raw
+    /// response. This is an advanced API intended for endpoints not yet supported by this crate,
+    /// such as those added by panel plugins. The authorization header and rate limit tracking are
+    /// applied as for any other request, and unsuccessful status codes are still returned as
+    /// errors
+    pub async fn raw_request(
+        &self,
+        method: Method,
+        endpoint: impl AsRef<str>,
+    ) -> crate::Result<Response> {
+        self.get_response::<_, NullErrorHandler>(method, endpoint.as_ref(), EmptyBody)
+            .await
+    }
+
+    /// Makes a request with the given body to an arbitrary endpoint relative to the client API
+    /// URL, returning the raw response. The body is sent as `application/json`. See
+    /// [`Client::raw_request`]
+    pub async fn raw_request_with_body(
+        &self,
+        method: Method,
+        endpoint: impl AsRef<str>,
+        body: impl Into<Body>,
+    ) -> crate::Result<Response> {
+        self.get_response::<_, NullErrorHandler>(
+            method,
+            endpoint.as_ref(),
+            RawBody(body, "application/json"),
+        )
+        .await
+    }
+
+    /// Gets a server with a specific ID, which can be used to make requests specific to that server.
+    /// The ID may be either the short identifier of the server, such as `1a7ce997`
+    /// ([`ServerStruct::identifier`]), or its full UUID ([`ServerStruct::uuid`]), since the panel
+    /// accepts both forms in every endpoint under `api/client/servers/{server}`
     pub fn get_server(&self, id: impl Into<String>) -> Server<'_> {
         Server {
             id: id.into(),
@@ -71,8 +285,22 @@ impl Client {
         }
     }
 
+    /// Gets a server with a specific UUID, which can be used to make requests specific to that
+    /// server. See [`Client::get_server`]
+    pub fn get_server_by_uuid(&self, uuid: Uuid) -> Server<'_> {
+        self.get_server(uuid.to_string())
+    }
+
+    /// Gets the server with the given ID, or `None` if it doesn't exist or isn't accessible with
+    /// this client. Unlike [`Client::get_server`], this makes a request to check that the server
+    /// exists
+    pub async fn try_get_server(&self, id: impl Into<String>) -> crate::Result<Option<Server<'_>>> {
+        let server = self.get_server(id);
+        Ok(server.exists().await?.then_some(server))
+    }
+
     #[cfg(test)]
-    pub(crate) fn get_test_server(&self) -> Server {
+    pub(crate) fn get_test_server(&self) -> Server<'_> {
         self.get_server(
             std::env::var("TEST_SERVER").expect("Expected TEST_SERVER in environment variables"),
         )
@@ -80,30 +308,69 @@ impl Client {
 }
 
 /// A builder for a client
+///
+/// # Testing
+/// Code using a client can be tested without a running Pterodactyl instance by pointing the client
+/// at a mock HTTP server, such as one provided by `wiremock` or `mockito`, which responds to the
+/// same paths as the panel. Requests are made to `{url}/api/client/{endpoint}`, with the same JSON
+/// bodies that the panel would send:
+/// ```no_run
+/// # use pterodactyl_api::client::ClientBuilder;
+/// # let mock_server_uri = String::new();
+/// // responds to GET /api/client with {"object": "list", "data": []}
+/// let client = ClientBuilder::new(mock_server_uri, "test-key").build();
+/// # async {
+/// assert!(client.list_servers().await?.is_empty());
+/// # Ok::<_, pterodactyl_api::Error>(())
+/// # };
+/// ```
 #[derive(Debug)]
 pub struct ClientBuilder {
     url: String,
+    api_prefix: String,
     client: Option<reqwest::Client>,
     api_key: String,
+    authenticated_client: bool,
+    etag_cache: bool,
+    concurrency_limit: Option<Arc<Semaphore>>,
+    compression: bool,
+    accept_invalid_certs: bool,
+    on_request_complete: Option<RequestCompleteCallback>,
 }
 
 impl ClientBuilder {
     /// Creates a new client builder, connecting to the given URL where a Pterodactyl server is
-    /// hosted, using the given API key for authentication
+    /// hosted, using the given API key for authentication. By default, requests are made to the
+    /// `api/client/` path under this URL, which can be changed using
+    /// [`ClientBuilder::with_api_prefix`]
     pub fn new(url: impl Into<String>, api_key: impl Into<String>) -> Self {
-        let mut url = url.into();
-        if !url.ends_with('/') {
-            url.push('/');
-        }
-        url.push_str("api/client/");
         Self {
-            url,
+            url: url.into(),
+            api_prefix: "api/client/".to_owned(),
             client: None,
             api_key: api_key.into(),
+            authenticated_client: false,
+            etag_cache: false,
+            concurrency_limit: None,
+            compression: true,
+            accept_invalid_certs: false,
+            on_request_complete: None,
+        }
+    }
+
+    /// Overrides the path appended to the URL to reach the client API, which is `api/client/` by
+    /// default. This is useful if the panel is reverse-proxied such that the client API is
+    /// reachable at a different path, in which case the prefix may also be empty
+    pub fn with_api_prefix(self, api_prefix: impl Into<String>) -> Self {
+        Self {
+            api_prefix: api_prefix.into(),
+            ..self
         }
     }
 
-    /// Uses the specified [`reqwest::Client`] for requests instead of making a default one
+    /// Uses the specified [`reqwest::Client`] for requests instead of making a default one. Whether
+    /// responses are decompressed is then determined by the given client rather than by
+    /// [`ClientBuilder::disable_compression`]
     pub fn with_client(self, client: reqwest::Client) -> Self {
         Self {
             client: Some(client),
@@ -111,13 +378,122 @@ impl ClientBuilder {
         }
     }
 
+    /// Uses the specified [`reqwest::Client`] for requests, which is expected to authenticate
+    /// requests itself, for example through a proxy or a custom middleware layer. No
+    /// `Authorization` header is sent by this crate, so the API key passed to
+    /// [`ClientBuilder::new`] is ignored and may be empty
+    pub fn with_authenticated_client(self, client: reqwest::Client) -> Self {
+        Self {
+            client: Some(client),
+            authenticated_client: true,
+            ..self
+        }
+    }
+
+    /// Enables caching of JSON responses to `GET` requests which have an `ETag` header. Subsequent
+    /// requests to the same endpoint will send an `If-None-Match` header, and if the panel responds
+    /// with `304 Not Modified`, the cached response will be reused. This reduces bandwidth for
+    /// frequently polled endpoints such as [`Server::get_details`] and [`Client::list_servers`],
+    /// but only takes effect if the panel sends `ETag` headers
+    pub fn with_etag_cache(self) -> Self {
+        Self {
+            etag_cache: true,
+            ..self
+        }
+    }
+
+    /// Limits the number of requests this client has in flight at once to `limit`. Requests
+    /// beyond this limit wait until an earlier request has received its response headers.
+    ///
+    /// The limit is per-[`Client`] (and shared with its clones). To share a limit between
+    /// multiple clients using the same API key, use [`ClientBuilder::with_semaphore`] with the same
+    /// semaphore instead
+    pub fn with_concurrency_limit(self, limit: usize) -> Self {
+        self.with_semaphore(Arc::new(Semaphore::new(limit)))
+    }
+
+    /// Limits the number of requests this client has in flight at once using the given semaphore,
+    /// which may be shared with other clients. See [`ClientBuilder::with_concurrency_limit`]
+    pub fn with_semaphore(self, semaphore: Arc<Semaphore>) -> Self {
+        Self {
+            concurrency_limit: Some(semaphore),
+            ..self
+        }
+    }
+
+    /// Calls the given callback whenever a response is received, with the endpoint relative to the
+    /// API URL, the status code of the response, and the time elapsed between sending the request
+    /// and receiving the response headers. This can be used to record the latency of the panel in
+    /// metrics. Requests which fail without a response, for example due to a connection error,
+    /// are not reported
+    pub fn on_request_complete(
+        self,
+        callback: impl Fn(&str, StatusCode, Duration) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            on_request_complete: Some(RequestCompleteCallback(Arc::new(callback))),
+            ..self
+        }
+    }
+
+    /// Disables compression of responses. By default, if the `gzip` or `brotli` features of this
+    /// crate are enabled, the client sends an `Accept-Encoding` header for those encodings and
+    /// transparently decompresses responses, which reduces bandwidth for large responses such as
+    /// file listings and downloads. Without these features, responses are never compressed and
+    /// this has no effect
+    pub fn disable_compression(self) -> Self {
+        Self {
+            compression: false,
+            ..self
+        }
+    }
+
+    /// Controls whether the client accepts invalid TLS certificates, such as self-signed or expired
+    /// certificates, or certificates for a different host name. This is off by default.
+    ///
+    /// # Warning
+    ///
+    /// This is insecure. Anyone able to intercept the connection to the panel can impersonate it
+    /// and read the API key and all requests made by this client. It should only be used for
+    /// panels on a trusted network. Prefer adding the panel's certificate as a trusted root
+    /// certificate to a [`reqwest::Client`] passed to [`ClientBuilder::with_client`] instead.
+    ///
+    /// Has no effect if a client is passed to [`ClientBuilder::with_client`] or
+    /// [`ClientBuilder::with_authenticated_client`]
+    pub fn danger_accept_invalid_certs(self, accept_invalid_certs: bool) -> Self {
+        Self {
+            accept_invalid_certs,
+            ..self
+        }
+    }
+
     /// Builds a client
     pub fn build(self) -> Client {
+        let compression = self.compression;
+        let accept_invalid_certs = self.accept_invalid_certs;
+        let client = self.client.unwrap_or_else(|| {
+            let builder =
+                reqwest::Client::builder().danger_accept_invalid_certs(accept_invalid_certs);
+            // the Accept-Encoding header is set by reqwest itself, since it only decompresses
+            // responses to requests for which it chose the encodings
+            #[cfg(feature = "gzip")]
+            let builder = builder.gzip(compression);
+            #[cfg(feature = "brotli")]
+            let builder = builder.brotli(compression);
+            #[cfg(not(any(feature = "gzip", feature = "brotli")))]
+            let _ = compression;
+            builder.build().unwrap_or_default()
+        });
         Client {
-            url: self.url,
-            client: self.client.unwrap_or_default(),
-            api_key: self.api_key,
-            rate_limits: RwLock::new(None),
+            url: crate::http::api_url(&self.url, &self.api_prefix),
+            client,
+            api_key: (!self.authenticated_client).then_some(self.api_key),
+            rate_limits: Arc::new(RwLock::new(None)),
+            etag_cache: self
+                .etag_cache
+                .then(|| Arc::new(RwLock::new(HashMap::new()))),
+            concurrency_limit: self.concurrency_limit,
+            on_request_complete: self.on_request_complete,
         }
     }
 }
@@ -138,6 +514,66 @@ impl Server<'_> {
             .map(|server| server.attributes)
     }
 
+    /// Gets details about this server, including the given relationships in
+    /// [`ServerStruct::relationships`]
+    pub async fn get_details_with_includes(
+        &self,
+        includes: &[ServerInclude],
+    ) -> crate::Result<ServerStruct> {
+        let options = includes
+            .iter()
+            .fold(ListOptions::new(), |options, include| {
+                options.with_include(include.name())
+            });
+        self.client
+            .request::<PteroObject<ServerStruct>>(
+                Method::GET,
+                &options.apply_to(&format!("servers/{}", self.id)),
+            )
+            .await
+            .map(|server| server.attributes)
+    }
+
+    /// Checks whether this server exists and is accessible with this client. Returns `Ok(false)`
+    /// instead of [`crate::Error::ResourceNotFound`] if it doesn't
+    pub async fn exists(&self) -> crate::Result<bool> {
+        match self.get_details().await {
+            Ok(_) => Ok(true),
+            Err(crate::Error::ResourceNotFound { .. }) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Checks whether the connected account has the given permission on this server, according to
+    /// the permissions the panel reports alongside the server details. Server owners and
+    /// administrators have all permissions. Note that this doesn't take into account restrictions
+    /// of the API key itself, so requests may still fail with [`crate::Error::PermissionError`]
+    pub async fn can(&self, permission: Permission) -> crate::Result<bool> {
+        #[derive(Deserialize)]
+        struct ServerMeta {
+            #[serde(default)]
+            user_permissions: Vec<String>,
+        }
+        #[derive(Deserialize)]
+        struct ServerWithMeta {
+            meta: ServerMeta,
+        }
+        let permissions = self
+            .client
+            .request::<ServerWithMeta>(Method::GET, &format!("servers/{}", self.id))
+            .await?
+            .meta
+            .user_permissions;
+        let permission = permission.as_str();
+        Ok(permissions.iter().any(|granted| {
+            granted == permission
+                || granted == "*"
+                || granted
+                    .strip_suffix('*')
+                    .is_some_and(|prefix| prefix.ends_with('.') && permission.starts_with(prefix))
+        }))
+    }
+
     /// Gets resources for this server
     pub async fn get_resources(&self) -> crate::Result<ServerResources> {
         self.client
@@ -149,14 +585,80 @@ impl Server<'_> {
             .map(|resources| resources.attributes)
     }
 
-    /// Sends a command to this server
+    /// Gets the current state of this server. This uses the same endpoint as
+    /// [`Server::get_resources`], as there is no lighter endpoint
+    pub async fn get_state(&self) -> crate::Result<ServerState> {
+        self.get_resources()
+            .await
+            .map(|resources| resources.current_state)
+    }
+
+    /// Polls the resources of this server every `poll_interval` until its state is `target`.
+    /// Returns [`crate::Error::Timeout`] if it didn't reach that state within `timeout`
+    pub async fn wait_for_state(
+        &self,
+        target: ServerState,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> crate::Result<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if self.get_state().await? == target {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() + poll_interval > deadline {
+                return Err(crate::Error::Timeout);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Polls the details of this server every `poll_interval` until it is no longer being
+    /// transferred to another node. Returns [`crate::Error::Timeout`] if the transfer didn't
+    /// complete within `timeout`. Note that a transfer which failed is also no longer in progress,
+    /// so callers should check which node the server is on afterwards if that matters
+    pub async fn wait_for_transfer_complete(
+        &self,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> crate::Result<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let transferring = self.get_details().await?.is_transferring;
+            if !transferring {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() + poll_interval > deadline {
+                return Err(crate::Error::Timeout);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Sends a command to this server. Returns [`crate::Error::ServerNotRunning`] if the server is
+    /// not running
     pub async fn send_command(&self, command: impl Into<String>) -> crate::Result<()> {
         #[derive(Serialize)]
         struct SendCommandBody {
             command: String,
         }
+        struct SendCommandErrorHandler;
+        impl ErrorHandler for SendCommandErrorHandler {
+            async fn get_error(response: Response) -> Option<crate::Error> {
+                // Wings refuses commands for an offline server, which the panel reports as a 502
+                if response.status() != StatusCode::BAD_GATEWAY {
+                    return None;
+                }
+                let error: ErrorResponse = response.json().await.ok()?;
+                if error.is_error("HttpException") {
+                    Some(crate::Error::ServerNotRunning)
+                } else {
+                    None
+                }
+            }
+        }
         self.client
-            .request_with_body::<EmptyBody, _>(
+            .request_with_error_handler::<EmptyBody, _, SendCommandErrorHandler>(
                 Method::POST,
                 &format!("servers/{}/command", self.id),
                 &SendCommandBody {
@@ -167,14 +669,30 @@ impl Server<'_> {
         Ok(())
     }
 
-    /// Sends a power signal to this server
+    /// Sends a power signal to this server. Returns [`crate::Error::InvalidPowerState`] if Wings
+    /// reports that the server is already in the state the signal would put it in. Other
+    /// conflicts, such as a server which is still installing, are returned as
+    /// [`crate::Error::Http`]
     pub async fn send_power_signal(&self, signal: PowerSignal) -> crate::Result<()> {
         #[derive(Serialize)]
         struct SendPowerSignalBody {
             signal: PowerSignal,
         }
+        struct SendPowerSignalErrorHandler;
+        impl ErrorHandler for SendPowerSignalErrorHandler {
+            async fn get_error(response: Response) -> Option<crate::Error> {
+                if response.status() != StatusCode::CONFLICT {
+                    return None;
+                }
+                let error: ErrorResponse = response.json().await.ok()?;
+                let current = error.already_in_state()?;
+                Some(crate::Error::InvalidPowerState {
+                    current: Some(current),
+                })
+            }
+        }
         self.client
-            .request_with_body::<EmptyBody, _>(
+            .request_with_error_handler::<EmptyBody, _, SendPowerSignalErrorHandler>(
                 Method::POST,
                 &format!("servers/{}/power", self.id),
                 &SendPowerSignalBody { signal },
@@ -182,6 +700,84 @@ impl Server<'_> {
             .await?;
         Ok(())
     }
+
+    /// Sends a power signal to this server, optionally waiting until the server has reached the
+    /// [target state](PowerSignal::target_state) of the signal. Awaiting the returned request
+    /// directly behaves like [`Server::send_power_signal`]
+    ///
+    /// ```no_run
+    /// # use pterodactyl_api::client::{PowerSignal, Server};
+    /// # use std::time::Duration;
+    /// # let server: Server = todo!();
+    /// # async {
+    /// server.power(PowerSignal::Stop).await?;
+    /// server
+    ///     .power(PowerSignal::Start)
+    ///     .wait_for_completion(Duration::from_secs(60))
+    ///     .await?;
+    /// # Ok::<_, pterodactyl_api::Error>(())
+    /// # };
+    /// ```
+    pub fn power(&self, signal: PowerSignal) -> PowerRequest<'_> {
+        PowerRequest {
+            server: self,
+            signal,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// A request to send a power signal to a server, created by [`Server::power`]
+#[derive(Debug)]
+#[must_use = "power requests do nothing unless awaited"]
+pub struct PowerRequest<'a> {
+    server: &'a Server<'a>,
+    signal: PowerSignal,
+    poll_interval: Duration,
+}
+
+impl PowerRequest<'_> {
+    /// Sets how often the state of the server is polled by [`PowerRequest::wait_for_completion`].
+    /// Defaults to one second
+    pub fn with_poll_interval(self, poll_interval: Duration) -> Self {
+        PowerRequest {
+            poll_interval,
+            ..self
+        }
+    }
+
+    /// Sends the power signal, then polls the resources of the server until it has reached the
+    /// [target state](PowerSignal::target_state) of the signal. When restarting, the server must
+    /// first be seen leaving the running state. Returns [`crate::Error::Timeout`] if the server
+    /// didn't reach the target state within `timeout`
+    pub async fn wait_for_completion(self, timeout: Duration) -> crate::Result<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        self.server.send_power_signal(self.signal).await?;
+        let target = self.signal.target_state();
+        let mut left_running = self.signal != PowerSignal::Restart;
+        loop {
+            let state = self.server.get_resources().await?.current_state;
+            if state != ServerState::Running {
+                left_running = true;
+            }
+            if left_running && state == target {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() + self.poll_interval > deadline {
+                return Err(crate::Error::Timeout);
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
+
+impl<'a> IntoFuture for PowerRequest<'a> {
+    type Output = crate::Result<()>;
+    type IntoFuture = Pin<Box<dyn Future<Output = crate::Result<()>> + Send + 'a>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move { self.server.send_power_signal(self.signal).await })
+    }
 }
 
 #[cfg(test)]
@@ -196,6 +792,94 @@ mod test {
         .build()
     }
 
+    #[test]
+    fn test_list_options() {
+        use crate::client::ListOptions;
+
+        assert_eq!(
+            ListOptions::new().apply_to("servers/1/backups"),
+            "servers/1/backups"
+        );
+        assert_eq!(
+            ListOptions::new()
+                .with_filter("name", "my server")
+                .with_sort("-created_at")
+                .with_include("egg")
+                .with_include("subusers")
+                .apply_to(""),
+            "?filter%5Bname%5D=my%20server&sort=-created_at&include=egg%2Csubusers"
+        );
+        assert_eq!(
+            ListOptions::new()
+                .with_sort("name")
+                .apply_to("files/list?directory=%2F"),
+            "files/list?directory=%2F&sort=name"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_server() {
+        let (url, server) = crate::http::mock_server(vec![(
+            reqwest::StatusCode::OK,
+            r#"{"object":"list","data":[]}"#.to_owned(),
+        )])
+        .await;
+
+        let completed = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let servers = ClientBuilder::new(url, "test-key")
+            .on_request_complete({
+                let completed = completed.clone();
+                move |endpoint, status, _| {
+                    completed
+                        .lock()
+                        .unwrap()
+                        .push((endpoint.to_owned(), status));
+                }
+            })
+            .build()
+            .list_servers()
+            .await
+            .unwrap();
+        assert!(servers.is_empty());
+        assert_eq!(
+            *completed.lock().unwrap(),
+            [(String::new(), reqwest::StatusCode::OK)]
+        );
+        let request = &server.await.unwrap()[0];
+        assert!(request.starts_with("GET /api/client/ HTTP/1.1"));
+        assert!(request
+            .to_ascii_lowercase()
+            .contains("authorization: bearer test-key"));
+    }
+
+    #[tokio::test]
+    async fn test_send_command_server_not_running() {
+        let error = |status: u16| {
+            format!(
+                r#"{{"errors":[{{"code":"HttpException","status":"{status}","detail":"Server must be online in order to send commands."}}]}}"#
+            )
+        };
+        let (url, server) = crate::http::mock_server(vec![
+            (reqwest::StatusCode::BAD_GATEWAY, error(502)),
+            (reqwest::StatusCode::CONFLICT, error(409)),
+        ])
+        .await;
+
+        let client = ClientBuilder::new(url, "test-key").build();
+        let server_handle = client.get_server("1");
+        assert!(matches!(
+            server_handle.send_command("say hi").await,
+            Err(crate::Error::ServerNotRunning)
+        ));
+        assert!(matches!(
+            server_handle.send_command("say hi").await,
+            Err(crate::Error::Http(reqwest::StatusCode::CONFLICT))
+        ));
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("POST /api/client/servers/1/command HTTP/1.1"));
+        assert!(requests[0].contains(r#"{"command":"say hi"}"#));
+    }
+
     #[tokio::test]
     async fn test_list_servers() {
         println!("{:?}", make_test_client().list_servers().await);
@@ -208,17 +892,17 @@ mod test {
 
     #[tokio::test]
     async fn test_get_account_details() {
-        println!("{:?}", make_test_client().get_account_details().await)
+        println!("{:?}", make_test_client().get_account_details().await);
     }
 
     #[tokio::test]
     async fn test_get_account_2fa_details() {
-        println!("{:?}", make_test_client().get_account_2fa_details().await)
+        println!("{:?}", make_test_client().get_account_2fa_details().await);
     }
 
     #[tokio::test]
     async fn test_get_api_keys() {
-        println!("{:?}", make_test_client().get_api_keys().await)
+        println!("{:?}", make_test_client().get_api_keys().await);
     }
 
     #[tokio::test]
@@ -226,7 +910,7 @@ mod test {
         println!(
             "{:?}",
             make_test_client().get_test_server().get_details().await
-        )
+        );
     }
 
     #[tokio::test]
@@ -234,7 +918,7 @@ mod test {
         println!(
             "{:?}",
             make_test_client().get_test_server().get_resources().await
-        )
+        );
     }
 
     #[tokio::test]
@@ -242,7 +926,7 @@ mod test {
         println!(
             "{:?}",
             make_test_client().get_test_server().list_databases().await
-        )
+        );
     }
 
     #[tokio::test]
@@ -250,7 +934,7 @@ mod test {
         println!(
             "{:?}",
             make_test_client().get_test_server().list_files("/").await
-        )
+        );
     }
 
     #[tokio::test]
@@ -261,6 +945,6 @@ mod test {
                 .get_test_server()
                 .file_contents_text("eula.txt")
                 .await
-        )
+        );
     }
 }
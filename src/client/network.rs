@@ -7,7 +7,7 @@ use reqwest::{Method, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 
 /// A network allocation on the server
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct Allocation {
     /// The ID of this allocation
@@ -47,23 +47,61 @@ impl Server<'_> {
             .map(|allocation| allocation.attributes)
     }
 
+    /// Automatically assigns a new network allocation with the given notes. The panel doesn't
+    /// support setting notes when creating an allocation, so if setting the notes fails, the new
+    /// allocation is deleted again and the error is returned
+    pub async fn create_network_allocation_with_notes(
+        &self,
+        notes: impl Into<String>,
+    ) -> crate::Result<Allocation> {
+        let allocation = self.create_network_allocation().await?;
+        match self
+            .set_network_allocation_notes(allocation.id, notes)
+            .await
+        {
+            Ok(allocation) => Ok(allocation),
+            Err(err) => {
+                // ignore errors here so that they don't mask the original error
+                let _ = self.delete_network_allocation(allocation.id).await;
+                Err(err)
+            }
+        }
+    }
+
     /// Sets the notes of a network allocation
     pub async fn set_network_allocation_notes(
         &self,
         allocation_id: u64,
         notes: impl Into<String>,
+    ) -> crate::Result<Allocation> {
+        self.set_network_allocation_optional_notes(allocation_id, Some(notes.into()))
+            .await
+    }
+
+    /// Clears the notes of a network allocation, so that [`Allocation::notes`] is `None`
+    pub async fn clear_network_allocation_notes(
+        &self,
+        allocation_id: u64,
+    ) -> crate::Result<Allocation> {
+        self.set_network_allocation_optional_notes(allocation_id, None)
+            .await
+    }
+
+    async fn set_network_allocation_optional_notes(
+        &self,
+        allocation_id: u64,
+        notes: Option<String>,
     ) -> crate::Result<Allocation> {
         #[derive(Serialize)]
         struct SetNetworkAllocationNotesBody {
-            notes: String,
+            // serialized as null when clearing the notes
+            notes: Option<String>,
         }
         self.client
             .request_with_body::<PteroObject<Allocation>, _>(
                 Method::POST,
                 &format!("servers/{}/network/allocations/{}", self.id, allocation_id),
-                &SetNetworkAllocationNotesBody {
-                    notes: notes.into(),
-                },
+                &SetNetworkAllocationNotesBody { notes },
             )
             .await
             .map(|allocation| allocation.attributes)
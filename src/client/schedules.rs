@@ -9,7 +9,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use time::OffsetDateTime;
 
 /// A task schedule for a server
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct Schedule {
     /// The ID of this schedule
@@ -27,6 +27,10 @@ pub struct Schedule {
     /// Whether this schedule is currently processing
     pub is_processing: bool,
 
+    /// Whether this schedule only runs while the server is online
+    #[serde(default)]
+    pub only_when_online: bool,
+
     /// When this schedule was last run
     #[serde(deserialize_with = "crate::structs::optional_iso_time")]
     pub last_run_at: Option<OffsetDateTime>,
@@ -83,6 +87,65 @@ impl Default for Cron {
     }
 }
 
+impl Cron {
+    /// Computes the next time after `after` at which this cron triggers, in the same UTC offset as
+    /// `after`. The panel evaluates schedules in its own timezone, so `after` should be given in
+    /// that timezone for the result to match [`Schedule::next_run_at`].
+    ///
+    /// As in standard cron, if both the day of the month and the day of the week are restricted
+    /// (neither contains `*`), the cron triggers on days matching either of them. Otherwise, days
+    /// must match both. Returns `None` if the cron never triggers, for example on the 30th of
+    /// February
+    pub fn next_occurrence(&self, after: OffsetDateTime) -> Option<OffsetDateTime> {
+        // cron triggers at most every few years for rules such as the 29th of February on a
+        // Monday, so this is enough to find any occurrence
+        const MAX_DAYS: u32 = 366 * 28;
+
+        let start = after
+            .checked_sub(time::Duration::seconds(i64::from(after.second())))?
+            .checked_sub(time::Duration::nanoseconds(i64::from(after.nanosecond())))?
+            .checked_add(time::Duration::MINUTE)?;
+        let either_day = !self.day_of_month.has_all() && !self.day_of_week.has_all();
+        let mut date = start.date();
+        for _ in 0..MAX_DAYS {
+            let day_of_month = self.day_of_month.matches(u32::from(date.day()), 1);
+            let weekday = u32::from(date.weekday().number_days_from_sunday());
+            // both 0 and 7 mean Sunday
+            let day_of_week = self.day_of_week.matches(weekday, 0)
+                || (weekday == 0 && self.day_of_week.matches(7, 0));
+            let day_matches = if either_day {
+                day_of_month || day_of_week
+            } else {
+                day_of_month && day_of_week
+            };
+            if day_matches && self.month.matches(u32::from(u8::from(date.month())), 1) {
+                let (min_hour, min_minute) = if date == start.date() {
+                    (start.hour(), start.minute())
+                } else {
+                    (0, 0)
+                };
+                for hour in min_hour..24 {
+                    if !self.hour.matches(u32::from(hour), 0) {
+                        continue;
+                    }
+                    let first_minute = if hour == min_hour { min_minute } else { 0 };
+                    if let Some(minute) =
+                        (first_minute..60).find(|&minute| self.minute.matches(u32::from(minute), 0))
+                    {
+                        return Some(
+                            date.with_hms(hour, minute, 0)
+                                .ok()?
+                                .assume_offset(after.offset()),
+                        );
+                    }
+                }
+            }
+            date = date.next_day()?;
+        }
+        None
+    }
+}
+
 /// Rules for a field of [`Cron`]
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct CronField {
@@ -139,6 +202,25 @@ macro_rules! cron_from_number {
 
 cron_from_number!(i8, u8, i16, u16, i32, u32);
 
+impl CronField {
+    fn has_all(&self) -> bool {
+        self.parts
+            .iter()
+            .any(|part| matches!(part, CronPart::All { .. }))
+    }
+
+    // `first` is the lowest value of the field, which steps of `*` count from
+    fn matches(&self, value: u32, first: u32) -> bool {
+        self.parts.iter().any(|part| match *part {
+            CronPart::All { step } => value >= first && (value - first).is_multiple_of(step.max(1)),
+            CronPart::Exact(exact) => value == exact,
+            CronPart::Range { min, max, step } => {
+                (min..=max).contains(&value) && (value - min).is_multiple_of(step.max(1))
+            }
+        })
+    }
+}
+
 impl From<CronPart> for CronField {
     fn from(value: CronPart) -> Self {
         CronField { parts: vec![value] }
@@ -255,7 +337,7 @@ impl Serialize for CronField {
 }
 
 /// The tasks for this schedule
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct ScheduleRelationships {
     /// The tasks for this schedule
@@ -264,7 +346,7 @@ pub struct ScheduleRelationships {
 }
 
 /// A task in a schedule
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct ScheduleTask {
     /// The ID of the task
@@ -370,11 +452,13 @@ impl Serialize for ScheduleAction {
 }
 
 /// The parameters to create a schedule
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, PartialEq, Eq, Clone)]
 pub struct ScheduleParams {
     name: String,
     #[serde(skip_serializing_if = "core::ops::Not::not")]
     is_active: bool,
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    only_when_online: bool,
     #[serde(flatten)]
     cron: Cron,
 }
@@ -385,6 +469,7 @@ impl ScheduleParams {
         ScheduleParams {
             name: name.into(),
             is_active: false,
+            only_when_online: false,
             cron: Cron::default(),
         }
     }
@@ -397,6 +482,14 @@ impl ScheduleParams {
         }
     }
 
+    /// Sets the schedule to only run while the server is online
+    pub fn set_only_when_online(self) -> Self {
+        ScheduleParams {
+            only_when_online: true,
+            ..self
+        }
+    }
+
     /// Sets the month(s) of the year the schedule will run at
     pub fn with_month(self, month: impl Into<CronField>) -> Self {
         ScheduleParams {
@@ -458,6 +551,7 @@ impl From<Schedule> for ScheduleParams {
         ScheduleParams {
             name: value.name,
             is_active: value.is_active,
+            only_when_online: value.only_when_online,
             cron: value.cron,
         }
     }
@@ -555,6 +649,46 @@ impl Server<'_> {
             .map(|schedule| schedule.attributes)
     }
 
+    /// Activates or deactivates the schedule with the given ID, keeping the rest of the schedule
+    /// unchanged
+    pub async fn set_schedule_active(&self, id: u64, active: bool) -> crate::Result<Schedule> {
+        let schedule = self.get_schedule(id).await?;
+        if schedule.is_active == active {
+            return Ok(schedule);
+        }
+        let params = ScheduleParams {
+            is_active: active,
+            ..schedule.into()
+        };
+        self.update_schedule(id, params).await
+    }
+
+    /// Gets whether the schedule with the given ID is currently processing
+    pub async fn is_schedule_processing(&self, id: u64) -> crate::Result<bool> {
+        self.get_schedule(id)
+            .await
+            .map(|schedule| schedule.is_processing)
+    }
+
+    /// Resets the processing state of the schedule with the given ID, recovering a schedule which
+    /// is stuck processing, for example after a failed backup task. The panel clears the processing
+    /// state whenever a schedule is activated or deactivated, so this toggles the schedule twice,
+    /// leaving it active or inactive as it was before
+    pub async fn reset_schedule(&self, id: u64) -> crate::Result<Schedule> {
+        let schedule = self.get_schedule(id).await?;
+        let active = schedule.is_active;
+        let params: ScheduleParams = schedule.into();
+        self.update_schedule(
+            id,
+            ScheduleParams {
+                is_active: !active,
+                ..params.clone()
+            },
+        )
+        .await?;
+        self.update_schedule(id, params).await
+    }
+
     /// Deletes the schedule with the given ID
     pub async fn delete_schedule(&self, id: u64) -> crate::Result<()> {
         self.client
@@ -616,3 +750,92 @@ impl Server<'_> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::client::schedules::{Cron, CronField, CronPart};
+    use time::format_description::well_known::Iso8601;
+    use time::OffsetDateTime;
+
+    fn at(time: &str) -> OffsetDateTime {
+        OffsetDateTime::parse(time, &Iso8601::DEFAULT).unwrap()
+    }
+
+    #[test]
+    fn test_next_occurrence() {
+        let daily = Cron {
+            hour: 3.into(),
+            minute: 0.into(),
+            ..Cron::default()
+        };
+        assert_eq!(
+            daily.next_occurrence(at("2024-05-10T12:34:56Z")),
+            Some(at("2024-05-11T03:00:00Z"))
+        );
+        assert_eq!(
+            daily.next_occurrence(at("2024-05-10T02:59:59Z")),
+            Some(at("2024-05-10T03:00:00Z"))
+        );
+        assert_eq!(
+            daily.next_occurrence(at("2024-05-10T03:00:00Z")),
+            Some(at("2024-05-11T03:00:00Z"))
+        );
+
+        let every_quarter_hour = Cron {
+            hour: CronPart::all().into(),
+            minute: CronPart::All { step: 15 }.into(),
+            ..Cron::default()
+        };
+        assert_eq!(
+            every_quarter_hour.next_occurrence(at("2024-12-31T23:50:00Z")),
+            Some(at("2025-01-01T00:00:00Z"))
+        );
+
+        // the 13th, or any Friday
+        let either_day = Cron {
+            day_of_month: 13.into(),
+            day_of_week: 5.into(),
+            ..Cron::default()
+        };
+        assert_eq!(
+            either_day.next_occurrence(at("2024-05-08T12:00:00Z")),
+            Some(at("2024-05-10T00:00:00Z"))
+        );
+        assert_eq!(
+            either_day.next_occurrence(at("2024-05-11T12:00:00Z")),
+            Some(at("2024-05-13T00:00:00Z"))
+        );
+
+        let weekdays = Cron {
+            day_of_week: CronField {
+                parts: vec![CronPart::Range {
+                    min: 1,
+                    max: 5,
+                    step: 1,
+                }],
+            },
+            ..Cron::default()
+        };
+        assert_eq!(
+            weekdays.next_occurrence(at("2024-05-10T12:00:00Z")),
+            Some(at("2024-05-13T00:00:00Z"))
+        );
+
+        let leap_day = Cron {
+            month: 2.into(),
+            day_of_month: 29.into(),
+            ..Cron::default()
+        };
+        assert_eq!(
+            leap_day.next_occurrence(at("2024-03-01T00:00:00Z")),
+            Some(at("2028-02-29T00:00:00Z"))
+        );
+
+        let never = Cron {
+            month: 2.into(),
+            day_of_month: 30.into(),
+            ..Cron::default()
+        };
+        assert_eq!(never.next_occurrence(at("2024-01-01T00:00:00Z")), None);
+    }
+}
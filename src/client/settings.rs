@@ -1,22 +1,86 @@
 //! API for endpoints under `api/client/servers/{server}/settings`
 
-use crate::client::Server;
+use crate::client::{Server, ServerStatus, ServerStruct};
 use crate::http::EmptyBody;
 use reqwest::Method;
 use serde::Serialize;
+use std::time::Duration;
 
 impl Server<'_> {
     /// Renames this server
     pub async fn rename(&self, name: impl Into<String>) -> crate::Result<()> {
+        self.rename_with_optional_description(name, None).await
+    }
+
+    /// Renames this server, then gets the updated details of this server, which can be used to
+    /// confirm that the new name was applied
+    pub async fn rename_and_fetch(&self, name: impl Into<String>) -> crate::Result<ServerStruct> {
+        self.rename(name).await?;
+        self.get_details().await
+    }
+
+    /// Renames this server and sets its description. Setting the description requires a panel
+    /// version which supports it, older versions ignore it
+    pub async fn rename_with_description(
+        &self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+    ) -> crate::Result<()> {
+        self.rename_with_optional_description(name, Some(description.into()))
+            .await
+    }
+
+    /// Sets the description of this server, keeping its current name. Requires a panel version
+    /// which supports setting the description, older versions ignore it.
+    ///
+    /// The panel requires the name to be sent along with the description, so the current name is
+    /// fetched first and sent back unchanged. If the server is renamed in between, that rename is
+    /// reverted. When the name is already known, use [`Server::rename_with_description`] instead,
+    /// which sends a single request
+    pub async fn set_description(&self, description: impl Into<String>) -> crate::Result<()> {
+        let name = self.get_details().await?.name;
+        self.rename_with_description(name, description).await
+    }
+
+    async fn rename_with_optional_description(
+        &self,
+        name: impl Into<String>,
+        description: Option<String>,
+    ) -> crate::Result<()> {
         #[derive(Serialize)]
         struct RenameBody {
             name: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            description: Option<String>,
         }
         self.client
             .request_with_body::<EmptyBody, _>(
                 Method::POST,
                 &format!("servers/{}/settings/rename", self.id),
-                &RenameBody { name: name.into() },
+                &RenameBody {
+                    name: name.into(),
+                    description,
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Sets the docker image of this server. The image must be one of the docker images allowed by
+    /// the egg of this server, which can be checked beforehand using
+    /// [`StartupData::allows_docker_image`](crate::client::startup::StartupData::allows_docker_image)
+    pub async fn set_docker_image(&self, image: impl Into<String>) -> crate::Result<()> {
+        #[derive(Serialize)]
+        struct SetDockerImageBody {
+            docker_image: String,
+        }
+        self.client
+            .request_with_body::<EmptyBody, _>(
+                Method::PUT,
+                &format!("servers/{}/settings/docker-image", self.id),
+                &SetDockerImageBody {
+                    docker_image: image.into(),
+                },
             )
             .await?;
         Ok(())
@@ -32,4 +96,29 @@ impl Server<'_> {
             .await?;
         Ok(())
     }
+
+    /// Reinstalls this server, then polls the details of this server every `poll_interval` until
+    /// it has finished installing. Returns [`crate::Error::InstallFailed`] if the installation
+    /// failed, or [`crate::Error::Timeout`] if it didn't finish within `timeout`
+    pub async fn reinstall_and_wait(
+        &self,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> crate::Result<()> {
+        self.reinstall().await?;
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            match self.get_details().await?.status {
+                Some(ServerStatus::Installing) => {}
+                Some(ServerStatus::InstallFailed | ServerStatus::ReinstallFailed) => {
+                    return Err(crate::Error::InstallFailed);
+                }
+                _ => return Ok(()),
+            }
+            if tokio::time::Instant::now() + poll_interval > deadline {
+                return Err(crate::Error::Timeout);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
 }
@@ -1,12 +1,13 @@
 //! API for endpoints under `api/client/servers/{server}/startup`
 
-use crate::client::Server;
+use crate::client::{ErrorResponse, Server};
+use crate::http::ErrorHandler;
 use crate::structs::PteroObject;
-use reqwest::Method;
+use reqwest::{Method, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 
 /// The startup data for a server
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct StartupData {
     /// The startup command without variables substituted
@@ -15,10 +16,26 @@ pub struct StartupData {
     pub raw_startup_command: String,
     /// The startup variables
     pub variables: Vec<Variable>,
+    /// The docker images the server is allowed to use, as pairs of a display label and the image.
+    /// Empty if the panel doesn't report them
+    pub docker_images: Vec<(String, String)>,
+}
+
+impl StartupData {
+    /// Whether the server is allowed to use the given docker image, which can be checked before
+    /// calling [`Server::set_docker_image`]. Always `true` if the panel doesn't report the allowed
+    /// docker images
+    pub fn allows_docker_image(&self, image: &str) -> bool {
+        self.docker_images.is_empty()
+            || self
+                .docker_images
+                .iter()
+                .any(|(_, allowed)| allowed == image)
+    }
 }
 
 /// A startup variable
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct Variable {
     /// The name of the variable
@@ -37,6 +54,148 @@ pub struct Variable {
     pub rules: String,
 }
 
+impl Variable {
+    /// Parses the [`rules`](Variable::rules) of this variable
+    pub fn parsed_rules(&self) -> VariableRules {
+        VariableRules::parse(&self.rules)
+    }
+}
+
+/// The parsed rules for what a startup variable can hold, which can be used to validate a value
+/// before sending it to the server
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct VariableRules {
+    /// Whether the variable must have a non-empty value
+    pub required: bool,
+    /// The type of value the variable must hold, if specified
+    pub value_type: Option<VariableType>,
+    /// The minimum value for numeric variables, or the minimum length for other variables
+    pub min: Option<i64>,
+    /// The maximum value for numeric variables, or the maximum length for other variables
+    pub max: Option<i64>,
+    /// The set of values the variable is allowed to hold, if specified
+    pub allowed_values: Option<Vec<String>>,
+    /// Rules that were not recognized, and are ignored during validation
+    pub unknown: Vec<String>,
+}
+
+/// The type of value a startup variable must hold
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum VariableType {
+    /// Any string
+    String,
+    /// An integer
+    Integer,
+    /// Any number
+    Numeric,
+    /// A boolean, either `true`, `false`, `1` or `0`
+    Boolean,
+}
+
+/// A reason why a value does not satisfy [`VariableRules`]
+#[derive(Debug, PartialEq, Clone, thiserror::Error)]
+#[non_exhaustive]
+pub enum RuleViolation {
+    /// The value is required but was empty
+    #[error("Value is required")]
+    Required,
+    /// The value is not an integer
+    #[error("Value must be an integer")]
+    NotInteger,
+    /// The value is not a number
+    #[error("Value must be numeric")]
+    NotNumeric,
+    /// The value is not a boolean
+    #[error("Value must be a boolean")]
+    NotBoolean,
+    /// The value (or its length) is less than the minimum
+    #[error("Value must be at least {0}")]
+    TooSmall(i64),
+    /// The value (or its length) is greater than the maximum
+    #[error("Value must be at most {0}")]
+    TooLarge(i64),
+    /// The value is not one of the allowed values
+    #[error("Value must be one of: {}", .0.join(", "))]
+    NotAllowed(Vec<String>),
+}
+
+impl VariableRules {
+    /// Parses a Laravel validation rule string such as `"required|string|max:20"`
+    pub fn parse(rules: &str) -> Self {
+        let mut result = VariableRules::default();
+        for rule in rules.split('|').filter(|rule| !rule.is_empty()) {
+            let (name, args) = match rule.split_once(':') {
+                Some((name, args)) => (name, Some(args)),
+                None => (rule, None),
+            };
+            match (name, args) {
+                ("required", None) => result.required = true,
+                ("string", None) => result.value_type = Some(VariableType::String),
+                ("integer", None) => result.value_type = Some(VariableType::Integer),
+                ("numeric", None) => result.value_type = Some(VariableType::Numeric),
+                ("boolean", None) => result.value_type = Some(VariableType::Boolean),
+                ("min", Some(min)) if min.parse::<i64>().is_ok() => result.min = min.parse().ok(),
+                ("max", Some(max)) if max.parse::<i64>().is_ok() => result.max = max.parse().ok(),
+                ("in", Some(values)) => {
+                    result.allowed_values =
+                        Some(values.split(',').map(|value| value.to_owned()).collect());
+                }
+                _ => result.unknown.push(rule.to_owned()),
+            }
+        }
+        result
+    }
+
+    /// Validates a value against these rules. Unknown rules are ignored
+    pub fn validate(&self, value: &str) -> Result<(), RuleViolation> {
+        if value.is_empty() {
+            return if self.required {
+                Err(RuleViolation::Required)
+            } else {
+                Ok(())
+            };
+        }
+
+        let size = match self.value_type {
+            Some(VariableType::Integer) => value
+                .parse::<i64>()
+                .map_err(|_err| RuleViolation::NotInteger)?
+                as f64,
+            Some(VariableType::Numeric) => value
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite())
+                .ok_or(RuleViolation::NotNumeric)?,
+            Some(VariableType::Boolean) => {
+                if !matches!(value, "true" | "false" | "1" | "0") {
+                    return Err(RuleViolation::NotBoolean);
+                }
+                value.chars().count() as f64
+            }
+            Some(VariableType::String) | None => value.chars().count() as f64,
+        };
+        if let Some(min) = self.min {
+            if size < min as f64 {
+                return Err(RuleViolation::TooSmall(min));
+            }
+        }
+        if let Some(max) = self.max {
+            if size > max as f64 {
+                return Err(RuleViolation::TooLarge(max));
+            }
+        }
+
+        if let Some(allowed_values) = &self.allowed_values {
+            if !allowed_values.iter().any(|allowed| allowed == value) {
+                return Err(RuleViolation::NotAllowed(allowed_values.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl Server<'_> {
     /// Gets the startup data for this server
     pub async fn get_startup_data(&self) -> crate::Result<StartupData> {
@@ -44,6 +203,8 @@ impl Server<'_> {
         struct StartupMeta {
             startup_command: String,
             raw_startup_command: String,
+            #[serde(default, deserialize_with = "crate::structs::ordered_string_map")]
+            docker_images: Vec<(String, String)>,
         }
         #[derive(Deserialize)]
         struct StartupDataObj {
@@ -57,10 +218,41 @@ impl Server<'_> {
                 startup_command: data.meta.startup_command,
                 raw_startup_command: data.meta.raw_startup_command,
                 variables: data.data.into_iter().map(|var| var.attributes).collect(),
+                docker_images: data.meta.docker_images,
             })
     }
 
-    /// Sets a startup variable for this server
+    /// Sets the startup command of this server.
+    ///
+    /// The client API has no endpoint to change the startup command on any released panel
+    /// version, so this always returns [`crate::Error::Unsupported`] without sending a request.
+    /// Use [`ApplicationClient::update_server_startup`](crate::application::ApplicationClient::update_server_startup)
+    /// with an application API key instead, then read the new command back with
+    /// [`Server::get_startup_data`].
+    pub async fn set_startup_command(&self, _cmd: impl Into<String>) -> crate::Result<StartupData> {
+        Err(crate::Error::Unsupported(
+            "ApplicationClient::update_server_startup",
+        ))
+    }
+
+    /// Gets the startup variable for this server with the given environment variable name (or
+    /// display name), or `None` if there is no such variable
+    pub async fn get_startup_variable(
+        &self,
+        env_variable: impl AsRef<str>,
+    ) -> crate::Result<Option<Variable>> {
+        let env_variable = env_variable.as_ref();
+        Ok(self
+            .get_startup_data()
+            .await?
+            .variables
+            .into_iter()
+            .find(|var| var.env_variable == env_variable || var.name == env_variable))
+    }
+
+    /// Sets a startup variable for this server. Returns [`crate::Error::VariableValidation`] if
+    /// the panel rejects the value, for example because it doesn't match the
+    /// [rules](Variable::rules) of the variable
     pub async fn set_startup_variable(
         &self,
         name: impl Into<String>,
@@ -71,8 +263,22 @@ impl Server<'_> {
             key: String,
             value: String,
         }
+        struct SetStartupVariableErrorHandler;
+        impl ErrorHandler for SetStartupVariableErrorHandler {
+            async fn get_error(response: Response) -> Option<crate::Error> {
+                if response.status() != StatusCode::UNPROCESSABLE_ENTITY {
+                    return None;
+                }
+                let error: ErrorResponse = response.json().await.ok()?;
+                let messages = error.details();
+                if messages.is_empty() {
+                    return None;
+                }
+                Some(crate::Error::VariableValidation { messages })
+            }
+        }
         self.client
-            .request_with_body::<PteroObject<Variable>, _>(
+            .request_with_error_handler::<PteroObject<Variable>, _, SetStartupVariableErrorHandler>(
                 Method::PUT,
                 &format!("servers/{}/startup/variable", self.id),
                 &SetStartupVariableBody {
@@ -83,4 +289,89 @@ impl Server<'_> {
             .await
             .map(|variable| variable.attributes)
     }
+
+    /// Sets multiple startup variables for this server. The variables are set one at a time, and
+    /// a failure to set one variable does not prevent the others from being set. Returns the
+    /// result of setting each variable, in the same order as they were given
+    pub async fn set_startup_variables<K, V>(
+        &self,
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Vec<(String, crate::Result<Variable>)>
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let mut results = Vec::new();
+        for (name, value) in vars {
+            let name = name.into();
+            let result = self.set_startup_variable(name.clone(), value).await;
+            results.push((name, result));
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::client::startup::{RuleViolation, VariableRules, VariableType};
+    use serde::Deserialize;
+
+    #[test]
+    fn test_docker_images() {
+        #[derive(Deserialize)]
+        struct Meta {
+            #[serde(default, deserialize_with = "crate::structs::ordered_string_map")]
+            docker_images: Vec<(String, String)>,
+        }
+        let meta: Meta = serde_json::from_str(
+            r#"{"docker_images":{"Java 21":"ghcr.io/java:21","Java 17":"ghcr.io/java:17"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            meta.docker_images,
+            [
+                ("Java 21".to_owned(), "ghcr.io/java:21".to_owned()),
+                ("Java 17".to_owned(), "ghcr.io/java:17".to_owned())
+            ]
+        );
+        let meta: Meta = serde_json::from_str(r#"{"docker_images":[]}"#).unwrap();
+        assert!(meta.docker_images.is_empty());
+        let meta: Meta = serde_json::from_str("{}").unwrap();
+        assert!(meta.docker_images.is_empty());
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let rules = VariableRules::parse("required|string|max:20|regex:/^[a-z]+$/");
+        assert!(rules.required);
+        assert_eq!(rules.value_type, Some(VariableType::String));
+        assert_eq!(rules.min, None);
+        assert_eq!(rules.max, Some(20));
+        assert_eq!(rules.unknown, vec!["regex:/^[a-z]+$/".to_owned()]);
+    }
+
+    #[test]
+    fn test_validate_rules() {
+        let rules = VariableRules::parse("required|integer|min:1|max:100");
+        assert_eq!(rules.validate("50"), Ok(()));
+        assert_eq!(rules.validate(""), Err(RuleViolation::Required));
+        assert_eq!(rules.validate("abc"), Err(RuleViolation::NotInteger));
+        assert_eq!(rules.validate("0"), Err(RuleViolation::TooSmall(1)));
+        assert_eq!(rules.validate("101"), Err(RuleViolation::TooLarge(100)));
+
+        let rules = VariableRules::parse("nullable|string|max:3");
+        assert_eq!(rules.validate(""), Ok(()));
+        assert_eq!(rules.validate("abcd"), Err(RuleViolation::TooLarge(3)));
+
+        let rules = VariableRules::parse("required|in:vanilla,forge,fabric");
+        assert_eq!(rules.validate("forge"), Ok(()));
+        assert!(matches!(
+            rules.validate("paper"),
+            Err(RuleViolation::NotAllowed(_))
+        ));
+
+        let rules = VariableRules::parse("required|boolean");
+        assert_eq!(rules.validate("1"), Ok(()));
+        assert_eq!(rules.validate("yes"), Err(RuleViolation::NotBoolean));
+    }
 }
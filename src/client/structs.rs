@@ -1,11 +1,14 @@
 use crate::client::network::Allocation;
-use serde::{Deserialize, Deserializer, Serialize};
+use crate::client::users::User;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
 use uuid::Uuid;
 
 /// A server
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 #[non_exhaustive]
 pub struct ServerStruct {
     /// Whether the connected account is the owner of this server
@@ -45,8 +48,73 @@ pub struct ServerStruct {
     pub relationships: ServerRelationships,
 }
 
+impl ServerStruct {
+    /// Whether the egg of this server has the given feature in [`ServerStruct::egg_features`]
+    pub fn has_feature(&self, feature: &EggFeature) -> bool {
+        self.egg_features
+            .iter()
+            .any(|name| name == feature.as_str())
+    }
+
+    /// Whether this server is in a state where it can be used normally, that is it is not being
+    /// installed, transferred or restored, its installation has not failed, it is not suspended,
+    /// and its node is not under maintenance. Note that an operational server may still be offline,
+    /// use [`ServerResources::can_accept_commands`] to check whether it is running
+    pub fn is_operational(&self) -> bool {
+        self.status.is_none() && !self.is_transferring && !self.is_node_under_maintenance
+    }
+
+    /// Gets the primary allocation of this server, which is the address players connect to
+    pub fn primary_allocation(&self) -> Option<&Allocation> {
+        self.relationships
+            .allocations
+            .iter()
+            .find(|allocation| allocation.is_default)
+    }
+
+    /// Gets the address of the primary allocation of this server in the form `ip:port`, using the
+    /// IP alias of the allocation if it has one. IPv6 addresses are enclosed in square brackets
+    pub fn connection_address(&self) -> Option<String> {
+        let allocation = self.primary_allocation()?;
+        let host = allocation
+            .ip_alias
+            .as_deref()
+            .filter(|alias| !alias.is_empty())
+            .unwrap_or(&allocation.ip);
+        if host.contains(':') {
+            Some(format!("[{}]:{}", host, allocation.port))
+        } else {
+            Some(format!("{}:{}", host, allocation.port))
+        }
+    }
+
+    /// Gets the details needed to connect to this server over SFTP as the account with the given
+    /// username. Pterodactyl expects SFTP usernames of the form `{username}.{identifier}`, where
+    /// `username` is the panel username of the account (not its email address) and `identifier`
+    /// is the short identifier of the server, e.g. `admin.1a7ce997`. The password is the panel
+    /// password of the account
+    pub fn sftp_connection(&self, account_username: &str) -> SftpDetails {
+        SftpDetails {
+            host: self.sftp_details.ip.clone(),
+            port: self.sftp_details.port,
+            username: format!("{}.{}", account_username, self.identifier),
+        }
+    }
+}
+
+/// The details needed to connect to a server over SFTP
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SftpDetails {
+    /// The host of the SFTP server
+    pub host: String,
+    /// The port of the SFTP server
+    pub port: u16,
+    /// The username to log in with
+    pub username: String,
+}
+
 /// Represents an IP and port combination
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
 pub struct IpAndPort {
     /// The IP
     #[serde(alias = "address")]
@@ -56,7 +124,7 @@ pub struct IpAndPort {
 }
 
 /// Virtual hardware limits for a server
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 #[non_exhaustive]
 pub struct ServerLimits {
     /// Maximum memory, or 0 for unlimited
@@ -70,7 +138,7 @@ pub struct ServerLimits {
     /// Maximum CPU usage, or 0 for unlimited
     pub cpu: f32,
     /// Which threads this server should run on, or an empty list for unrestricted
-    #[serde(deserialize_with = "threads")]
+    #[serde(deserialize_with = "threads", serialize_with = "serialize_threads")]
     pub threads: Option<Vec<u64>>,
     /// Whether the out of memory killer is enabled on this server, or None for unknown
     pub oom_killer: Option<bool>,
@@ -94,8 +162,24 @@ where
     }
 }
 
+fn serialize_threads<S>(threads: &Option<Vec<u64>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    threads
+        .as_ref()
+        .map(|threads| {
+            threads
+                .iter()
+                .map(|thread| thread.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .serialize(serializer)
+}
+
 /// Limits for various feature on the server
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct ServerFeatureLimits {
     /// The maximum number of databases
@@ -107,7 +191,7 @@ pub struct ServerFeatureLimits {
 }
 
 /// The status of a server
-#[derive(Debug, Deserialize, PartialEq, Eq, Hash, Copy, Clone)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Copy, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum ServerStatus {
     /// This server is being installed
@@ -122,17 +206,90 @@ pub enum ServerStatus {
     RestoringBackup,
 }
 
+impl Display for ServerStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServerStatus::Installing => f.write_str("installing"),
+            ServerStatus::InstallFailed => f.write_str("install_failed"),
+            ServerStatus::ReinstallFailed => f.write_str("reinstall_failed"),
+            ServerStatus::Suspended => f.write_str("suspended"),
+            ServerStatus::RestoringBackup => f.write_str("restoring_backup"),
+        }
+    }
+}
+
+impl FromStr for ServerStatus {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "installing" => Ok(ServerStatus::Installing),
+            "install_failed" => Ok(ServerStatus::InstallFailed),
+            "reinstall_failed" => Ok(ServerStatus::ReinstallFailed),
+            "suspended" => Ok(ServerStatus::Suspended),
+            "restoring_backup" => Ok(ServerStatus::RestoringBackup),
+            _ => Err(ParseEnumError(s.to_owned())),
+        }
+    }
+}
+
 /// Extra metadata for a server
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct ServerRelationships {
     /// The network allocations of this server
-    #[serde(deserialize_with = "crate::structs::ptero_list")]
+    #[serde(
+        deserialize_with = "crate::structs::ptero_list",
+        serialize_with = "crate::structs::serialize_ptero_list"
+    )]
     pub allocations: Vec<Allocation>,
+    /// The egg of this server, if requested with [`ServerInclude::Egg`]
+    #[serde(
+        default,
+        deserialize_with = "crate::structs::optional_ptero_object",
+        serialize_with = "crate::structs::serialize_optional_ptero_object"
+    )]
+    pub egg: Option<ServerEgg>,
+    /// The subusers of this server, if requested with [`ServerInclude::Subusers`]
+    #[serde(
+        default,
+        deserialize_with = "crate::structs::optional_ptero_list",
+        serialize_with = "crate::structs::serialize_optional_ptero_list"
+    )]
+    pub subusers: Option<Vec<User>>,
+}
+
+/// Extra relationships which can be included in server details
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[non_exhaustive]
+pub enum ServerInclude {
+    /// The egg of the server
+    Egg,
+    /// The subusers of the server
+    Subusers,
+}
+
+impl ServerInclude {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ServerInclude::Egg => "egg",
+            ServerInclude::Subusers => "subusers",
+        }
+    }
+}
+
+/// The egg of a server
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct ServerEgg {
+    /// The UUID of this egg
+    pub uuid: Uuid,
+    /// The name of this egg
+    pub name: String,
 }
 
 /// A group of permissions
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
 pub struct PermissionGroup {
     /// The description of this group
     pub description: String,
@@ -141,8 +298,307 @@ pub struct PermissionGroup {
     pub keys: HashMap<String, String>,
 }
 
+macro_rules! string_enum {
+    (
+        $(#[doc = $enum_doc:literal])*
+        pub enum $name:ident {
+            $(#[doc = $unknown_doc:literal])*
+            Unknown(String),
+            $($(#[doc = $doc:literal])* $variant:ident => $key:literal,)*
+        }
+    ) => {
+        $(#[doc = $enum_doc])*
+        #[derive(Debug, PartialEq, Eq, Hash, Clone)]
+        #[non_exhaustive]
+        pub enum $name {
+            $(
+                $(#[doc = $doc])*
+                $variant,
+            )*
+            $(#[doc = $unknown_doc])*
+            Unknown(String),
+        }
+
+        impl $name {
+            #[doc = concat!("Gets the name of this ", stringify!($name), " as used by the panel")]
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $key,)*
+                    $name::Unknown(name) => name,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                match value {
+                    $($key => $name::$variant,)*
+                    _ => $name::Unknown(value.to_owned()),
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                match $name::from(value.as_str()) {
+                    $name::Unknown(_) => $name::Unknown(value),
+                    known => known,
+                }
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = std::convert::Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok($name::from(s))
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                String::deserialize(deserializer).map($name::from)
+            }
+        }
+    };
+}
+
+string_enum! {
+    /// A permission which can be granted to a subuser of a server, e.g. `control.start`
+    pub enum Permission {
+        /// A permission not known to this crate, such as one added by a panel addon
+        Unknown(String),
+        /// Connect to the websocket of the server
+        WebsocketConnect => "websocket.connect",
+        /// Send commands to the server console
+        ControlConsole => "control.console",
+        /// Start the server
+        ControlStart => "control.start",
+        /// Stop the server
+        ControlStop => "control.stop",
+        /// Restart the server
+        ControlRestart => "control.restart",
+        /// Create subusers
+        UserCreate => "user.create",
+        /// View subusers and their permissions
+        UserRead => "user.read",
+        /// Modify subusers
+        UserUpdate => "user.update",
+        /// Delete subusers
+        UserDelete => "user.delete",
+        /// Create files and directories
+        FileCreate => "file.create",
+        /// List files and directories
+        FileRead => "file.read",
+        /// View and download the contents of files
+        FileReadContent => "file.read-content",
+        /// Modify files and directories
+        FileUpdate => "file.update",
+        /// Delete files and directories
+        FileDelete => "file.delete",
+        /// Compress and decompress files
+        FileArchive => "file.archive",
+        /// Connect over SFTP
+        FileSftp => "file.sftp",
+        /// Create backups
+        BackupCreate => "backup.create",
+        /// View backups
+        BackupRead => "backup.read",
+        /// Delete backups
+        BackupDelete => "backup.delete",
+        /// Download backups
+        BackupDownload => "backup.download",
+        /// Restore backups
+        BackupRestore => "backup.restore",
+        /// View network allocations
+        AllocationRead => "allocation.read",
+        /// Create network allocations
+        AllocationCreate => "allocation.create",
+        /// Modify network allocations
+        AllocationUpdate => "allocation.update",
+        /// Delete network allocations
+        AllocationDelete => "allocation.delete",
+        /// View startup variables
+        StartupRead => "startup.read",
+        /// Modify startup variables
+        StartupUpdate => "startup.update",
+        /// Change the docker image
+        StartupDockerImage => "startup.docker-image",
+        /// Create databases
+        DatabaseCreate => "database.create",
+        /// View databases
+        DatabaseRead => "database.read",
+        /// Rotate database passwords
+        DatabaseUpdate => "database.update",
+        /// Delete databases
+        DatabaseDelete => "database.delete",
+        /// View database passwords
+        DatabaseViewPassword => "database.view_password",
+        /// Create schedules
+        ScheduleCreate => "schedule.create",
+        /// View schedules
+        ScheduleRead => "schedule.read",
+        /// Modify schedules
+        ScheduleUpdate => "schedule.update",
+        /// Delete schedules
+        ScheduleDelete => "schedule.delete",
+        /// Rename the server
+        SettingsRename => "settings.rename",
+        /// Reinstall the server
+        SettingsReinstall => "settings.reinstall",
+        /// View the activity log
+        ActivityRead => "activity.read",
+    }
+}
+
+string_enum! {
+    /// A feature of the egg of a server, which enables extra functionality in the panel
+    pub enum EggFeature {
+        /// A feature not known to this crate, such as one used by a custom panel frontend
+        Unknown(String),
+        /// The server requires the Minecraft EULA to be accepted before it can start
+        Eula => "eula",
+        /// The Java version used by the server can be changed
+        JavaVersion => "java_version",
+        /// The server may hit the process limit of its container
+        PidLimit => "pid_limit",
+        /// The server may run out of disk space while installing through Steam
+        SteamDiskSpace => "steam_disk_space",
+        /// The server requires a game server login token
+        GslToken => "gsl_token",
+    }
+}
+
+/// A set of permissions to grant to a subuser, which can be passed to
+/// [`Server::add_user`](crate::client::Server::add_user) and
+/// [`Server::set_user_permissions`](crate::client::Server::set_user_permissions) after converting
+/// it into a `Vec<String>`
+///
+/// ```no_run
+/// # use pterodactyl_api::client::{Permission, PermissionSet, Server};
+/// # let server: Server = todo!();
+/// # async {
+/// let permissions = PermissionSet::operator().with(Permission::BackupCreate);
+/// server.add_user("user@example.com", permissions.into()).await?;
+/// # Ok::<_, pterodactyl_api::Error>(())
+/// # };
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct PermissionSet {
+    permissions: Vec<Permission>,
+}
+
+impl PermissionSet {
+    /// Creates an empty permission set
+    pub fn new() -> Self {
+        PermissionSet::default()
+    }
+
+    /// A preset which allows viewing the server without changing anything. Contains
+    /// [`Permission::WebsocketConnect`], [`Permission::FileRead`], [`Permission::FileReadContent`],
+    /// [`Permission::BackupRead`], [`Permission::AllocationRead`], [`Permission::StartupRead`],
+    /// [`Permission::DatabaseRead`], [`Permission::ScheduleRead`], [`Permission::UserRead`] and
+    /// [`Permission::ActivityRead`]
+    pub fn read_only() -> Self {
+        PermissionSet {
+            permissions: vec![
+                Permission::WebsocketConnect,
+                Permission::FileRead,
+                Permission::FileReadContent,
+                Permission::BackupRead,
+                Permission::AllocationRead,
+                Permission::StartupRead,
+                Permission::DatabaseRead,
+                Permission::ScheduleRead,
+                Permission::UserRead,
+                Permission::ActivityRead,
+            ],
+        }
+    }
+
+    /// A preset which allows operating the server, but not changing its files or configuration.
+    /// Contains all the permissions of [`PermissionSet::read_only`], as well as
+    /// [`Permission::ControlConsole`], [`Permission::ControlStart`], [`Permission::ControlStop`] and
+    /// [`Permission::ControlRestart`]
+    pub fn operator() -> Self {
+        PermissionSet::read_only()
+            .with(Permission::ControlConsole)
+            .with(Permission::ControlStart)
+            .with(Permission::ControlStop)
+            .with(Permission::ControlRestart)
+    }
+
+    /// Adds a permission to this set, if it isn't already in it
+    pub fn with(mut self, permission: Permission) -> Self {
+        if !self.permissions.contains(&permission) {
+            self.permissions.push(permission);
+        }
+        self
+    }
+
+    /// Removes a permission from this set
+    pub fn without(mut self, permission: &Permission) -> Self {
+        self.permissions.retain(|p| p != permission);
+        self
+    }
+
+    /// Whether this set contains the given permission
+    pub fn contains(&self, permission: &Permission) -> bool {
+        self.permissions.contains(permission)
+    }
+
+    /// Gets the permissions in this set
+    pub fn permissions(&self) -> &[Permission] {
+        &self.permissions
+    }
+}
+
+impl From<PermissionSet> for Vec<Permission> {
+    fn from(value: PermissionSet) -> Self {
+        value.permissions
+    }
+}
+
+impl From<PermissionSet> for Vec<String> {
+    fn from(value: PermissionSet) -> Self {
+        value
+            .permissions
+            .into_iter()
+            .map(|permission| match permission {
+                Permission::Unknown(name) => name,
+                permission => permission.as_str().to_owned(),
+            })
+            .collect()
+    }
+}
+
+impl FromIterator<Permission> for PermissionSet {
+    fn from_iter<T: IntoIterator<Item = Permission>>(iter: T) -> Self {
+        iter.into_iter()
+            .fold(PermissionSet::new(), PermissionSet::with)
+    }
+}
+
 /// Represents the current resources of a server
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 #[non_exhaustive]
 pub struct ServerResources {
     /// The current state of the server
@@ -153,8 +609,20 @@ pub struct ServerResources {
     pub resources: ServerResourcesResources,
 }
 
+impl ServerResources {
+    /// Whether the server can currently accept console commands, that is it is not suspended and is
+    /// either starting or running
+    pub fn can_accept_commands(&self) -> bool {
+        !self.is_suspended
+            && matches!(
+                self.current_state,
+                ServerState::Starting | ServerState::Running
+            )
+    }
+}
+
 /// Represents resource usage of a server
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 #[non_exhaustive]
 pub struct ServerResourcesResources {
     /// The amount of memory used, in bytes
@@ -167,12 +635,49 @@ pub struct ServerResourcesResources {
     pub network_rx_bytes: u64,
     /// The amount of data received over the network, in bytes
     pub network_tx_bytes: u64,
-    /// Uptime in seconds
+    /// Uptime in milliseconds, see [`ServerResourcesResources::uptime_duration`]
     pub uptime: u64,
 }
 
+impl ServerResourcesResources {
+    /// The uptime of the server as a [`Duration`]. Wings reports the uptime in milliseconds,
+    /// rather than seconds
+    pub fn uptime_duration(&self) -> Duration {
+        Duration::from_millis(self.uptime)
+    }
+
+    /// The memory usage as a percentage of the memory limit of the server, or `None` if the
+    /// memory is unlimited. Note that the limit is in MiB, whereas the usage is in bytes
+    pub fn memory_percent(&self, limits: &ServerLimits) -> Option<f64> {
+        bytes_percent_of_mib(self.memory_bytes, limits.memory)
+    }
+
+    /// The disk usage as a percentage of the disk limit of the server, or `None` if the disk space
+    /// is unlimited. Note that the limit is in MiB, whereas the usage is in bytes
+    pub fn disk_percent(&self, limits: &ServerLimits) -> Option<f64> {
+        bytes_percent_of_mib(self.disk_bytes, limits.disk)
+    }
+
+    /// The CPU usage as a percentage of the CPU limit of the server, or `None` if the CPU usage is
+    /// unlimited. Both the usage and the limit are percentages of a single core, so a server using
+    /// 150% of a 200% limit is at 75% of its limit
+    pub fn cpu_percent_of_limit(&self, limits: &ServerLimits) -> Option<f64> {
+        if limits.cpu <= 0.0 {
+            return None;
+        }
+        Some(f64::from(self.cpu_absolute) / f64::from(limits.cpu) * 100.0)
+    }
+}
+
+fn bytes_percent_of_mib(bytes: u64, limit_mib: u64) -> Option<f64> {
+    if limit_mib == 0 {
+        return None;
+    }
+    Some(bytes as f64 / (limit_mib as f64 * 1024.0 * 1024.0) * 100.0)
+}
+
 /// The state of a server
-#[derive(Debug, Deserialize, PartialEq, Eq, Hash, Copy, Clone)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Copy, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum ServerState {
     /// The server is offline
@@ -185,6 +690,36 @@ pub enum ServerState {
     Stopping,
 }
 
+impl Display for ServerState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServerState::Offline => f.write_str("offline"),
+            ServerState::Starting => f.write_str("starting"),
+            ServerState::Running => f.write_str("running"),
+            ServerState::Stopping => f.write_str("stopping"),
+        }
+    }
+}
+
+impl FromStr for ServerState {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "offline" => Ok(ServerState::Offline),
+            "starting" => Ok(ServerState::Starting),
+            "running" => Ok(ServerState::Running),
+            "stopping" => Ok(ServerState::Stopping),
+            _ => Err(ParseEnumError(s.to_owned())),
+        }
+    }
+}
+
+/// An error returned when parsing an enum from a string which doesn't match any of its values
+#[derive(Debug, PartialEq, Eq, Clone, thiserror::Error)]
+#[error("Unknown value: {0}")]
+pub struct ParseEnumError(pub String);
+
 /// A power signal to send to the server
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Copy, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -199,6 +734,17 @@ pub enum PowerSignal {
     Kill,
 }
 
+impl PowerSignal {
+    /// Gets the state the server ends up in after this signal has been handled. Note that a
+    /// restarting server passes through other states before it is running again
+    pub fn target_state(self) -> ServerState {
+        match self {
+            PowerSignal::Start | PowerSignal::Restart => ServerState::Running,
+            PowerSignal::Stop | PowerSignal::Kill => ServerState::Offline,
+        }
+    }
+}
+
 impl Display for PowerSignal {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -219,9 +765,220 @@ impl ErrorResponse {
     pub(crate) fn is_error(&self, error: &str) -> bool {
         self.errors.iter().any(|e| e.code == error)
     }
+
+    /// Gets the human-readable details of the errors, such as validation messages
+    pub(crate) fn details(&self) -> Vec<String> {
+        self.errors
+            .iter()
+            .filter_map(|e| e.detail.clone())
+            .collect()
+    }
+
+    /// Whether the panel rejected the request because the server is suspended
+    pub(crate) fn is_suspended(&self) -> bool {
+        self.errors.iter().any(|e| {
+            (e.code == "ServerStateConflictException" || e.code == "ConflictHttpException")
+                && e.detail
+                    .as_deref()
+                    .is_some_and(|detail| detail.to_ascii_lowercase().contains("suspended"))
+        })
+    }
+
+    /// Finds the state the error details say the server is already in, such as "server is
+    /// already running". Other mentions of states are ambiguous, such as "server is not running",
+    /// so they are ignored
+    pub(crate) fn already_in_state(&self) -> Option<ServerState> {
+        self.errors
+            .iter()
+            .filter_map(|e| e.detail.as_deref())
+            .find_map(|detail| {
+                let words = detail
+                    .split(|c: char| !c.is_ascii_alphabetic())
+                    .filter(|word| !word.is_empty())
+                    .map(str::to_ascii_lowercase)
+                    .collect::<Vec<_>>();
+                words.windows(2).find_map(|pair| match pair {
+                    [already, state] if already == "already" => state.parse().ok(),
+                    _ => None,
+                })
+            })
+    }
 }
 
 #[derive(Deserialize)]
 pub(crate) struct ErrorResponseError {
     pub(crate) code: String,
+    #[serde(default)]
+    pub(crate) detail: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        EggFeature, ErrorResponse, ParseEnumError, Permission, PermissionSet, ServerLimits,
+        ServerResourcesResources, ServerState, ServerStatus,
+    };
+
+    fn limits(memory: u64, disk: u64, cpu: f32) -> ServerLimits {
+        ServerLimits {
+            memory,
+            swap: 0,
+            disk,
+            io: 500,
+            cpu,
+            threads: None,
+            oom_killer: None,
+        }
+    }
+
+    #[test]
+    fn test_resource_percentages() {
+        let resources = ServerResourcesResources {
+            memory_bytes: 512 * 1024 * 1024,
+            cpu_absolute: 150.0,
+            disk_bytes: 256 * 1024 * 1024,
+            network_rx_bytes: 0,
+            network_tx_bytes: 0,
+            uptime: 90_000,
+        };
+        let limits = limits(1024, 1024, 200.0);
+        assert_eq!(resources.memory_percent(&limits), Some(50.0));
+        assert_eq!(resources.disk_percent(&limits), Some(25.0));
+        assert_eq!(resources.cpu_percent_of_limit(&limits), Some(75.0));
+
+        assert_eq!(
+            resources.uptime_duration(),
+            std::time::Duration::from_secs(90)
+        );
+
+        let unlimited = self::limits(0, 0, 0.0);
+        assert_eq!(resources.memory_percent(&unlimited), None);
+        assert_eq!(resources.disk_percent(&unlimited), None);
+        assert_eq!(resources.cpu_percent_of_limit(&unlimited), None);
+    }
+
+    #[test]
+    fn test_server_state_and_status_strings() {
+        for state in [
+            ServerState::Offline,
+            ServerState::Starting,
+            ServerState::Running,
+            ServerState::Stopping,
+        ] {
+            assert_eq!(state.to_string().parse(), Ok(state));
+            assert_eq!(
+                serde_json::to_value(state).unwrap(),
+                serde_json::Value::String(state.to_string())
+            );
+        }
+        for status in [
+            ServerStatus::Installing,
+            ServerStatus::InstallFailed,
+            ServerStatus::ReinstallFailed,
+            ServerStatus::Suspended,
+            ServerStatus::RestoringBackup,
+        ] {
+            assert_eq!(status.to_string().parse(), Ok(status));
+            assert_eq!(
+                serde_json::to_value(status).unwrap(),
+                serde_json::Value::String(status.to_string())
+            );
+        }
+        assert_eq!(
+            "Running".parse::<ServerState>(),
+            Err(ParseEnumError("Running".to_owned()))
+        );
+        assert_eq!(
+            "transferring".parse::<ServerStatus>(),
+            Err(ParseEnumError("transferring".to_owned()))
+        );
+        assert_eq!(
+            ParseEnumError("transferring".to_owned()).to_string(),
+            "Unknown value: transferring"
+        );
+    }
+
+    #[test]
+    fn test_permission_names() {
+        assert_eq!(Permission::from("control.start"), Permission::ControlStart);
+        assert_eq!(
+            Permission::from("file.read-content"),
+            Permission::FileReadContent
+        );
+        assert_eq!(
+            Permission::DatabaseViewPassword.as_str(),
+            "database.view_password"
+        );
+        assert_eq!(
+            Permission::from("addon.thing"),
+            Permission::Unknown("addon.thing".to_owned())
+        );
+        assert_eq!(
+            Permission::Unknown("addon.thing".to_owned()).to_string(),
+            "addon.thing"
+        );
+    }
+
+    #[test]
+    fn test_permission_set() {
+        let operator = PermissionSet::operator();
+        assert!(PermissionSet::read_only()
+            .permissions()
+            .iter()
+            .all(|permission| operator.contains(permission)));
+        assert!(operator.contains(&Permission::ControlStart));
+        assert!(!PermissionSet::read_only().contains(&Permission::ControlStart));
+        let names: Vec<String> = PermissionSet::new()
+            .with(Permission::ControlStart)
+            .with(Permission::ControlStart)
+            .with(Permission::Unknown("addon.thing".to_owned()))
+            .into();
+        assert_eq!(names, ["control.start", "addon.thing"]);
+    }
+
+    #[test]
+    fn test_egg_feature_names() {
+        assert_eq!(EggFeature::from("eula"), EggFeature::Eula);
+        assert_eq!(EggFeature::JavaVersion.as_str(), "java_version");
+        assert_eq!(
+            serde_json::from_str::<EggFeature>(r#""custom""#).unwrap(),
+            EggFeature::Unknown("custom".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_error_already_in_state() {
+        let error: ErrorResponse = serde_json::from_str(
+            r#"{"errors":[{"code":"ConflictHttpException","status":"409","detail":"Cannot start server: server is already Running."}]}"#,
+        )
+        .unwrap();
+        assert_eq!(error.already_in_state(), Some(ServerState::Running));
+        let error: ErrorResponse = serde_json::from_str(
+            r#"{"errors":[{"code":"ConflictHttpException","status":"409","detail":"Cannot send command: server is not running."}]}"#,
+        )
+        .unwrap();
+        assert_eq!(error.already_in_state(), None);
+        let error: ErrorResponse = serde_json::from_str(
+            r#"{"errors":[{"code":"ConflictHttpException","status":"409","detail":"Cannot stop server while starting, it will be running soon."}]}"#,
+        )
+        .unwrap();
+        assert_eq!(error.already_in_state(), None);
+        let error: ErrorResponse =
+            serde_json::from_str(r#"{"errors":[{"code":"HttpException"}]}"#).unwrap();
+        assert_eq!(error.already_in_state(), None);
+    }
+
+    #[test]
+    fn test_error_is_suspended() {
+        let error: ErrorResponse = serde_json::from_str(
+            r#"{"errors":[{"code":"ServerStateConflictException","status":"409","detail":"This server is currently suspended and the functionality requested is unavailable."}]}"#,
+        )
+        .unwrap();
+        assert!(error.is_suspended());
+        let error: ErrorResponse = serde_json::from_str(
+            r#"{"errors":[{"code":"ServerStateConflictException","status":"409","detail":"This server has not yet completed its installation process, please try again later."}]}"#,
+        )
+        .unwrap();
+        assert!(!error.is_suspended());
+    }
 }
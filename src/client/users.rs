@@ -9,7 +9,7 @@ use time::OffsetDateTime;
 use uuid::Uuid;
 
 /// A user on a server that holds permissions for that server
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct User {
     /// The ID of this user
@@ -29,7 +29,10 @@ pub struct User {
     pub two_factor_enabled: bool,
 
     /// When this user was added to this server
-    #[serde(deserialize_with = "crate::structs::iso_time")]
+    #[serde(
+        deserialize_with = "crate::structs::iso_time",
+        serialize_with = "crate::structs::serialize_iso_time"
+    )]
     pub created_at: OffsetDateTime,
 
     /// The permissions of this user
@@ -1,15 +1,17 @@
 //! A Pterodactyl websocket client
 
 use crate::client::{PowerSignal, Server, ServerState};
-use crate::Error::WebsocketTokenExpired;
 use async_tungstenite::tungstenite::Message;
 use async_tungstenite::WebSocketStream;
 use futures_io::{AsyncRead, AsyncWrite};
-use futures_util::{SinkExt, StreamExt};
+use futures_util::StreamExt;
 use reqwest::Method;
 use serde::de::value::StrDeserializer;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[doc(hidden)]
 mod sealed {
@@ -24,7 +26,10 @@ struct WebSocketImpl<'a, S, L> {
     server: &'a Server<'a>,
     socket: WebSocketStream<S>,
     listener: L,
+    options: WebSocketOptions,
     ready: bool,
+    last_stats: Option<ServerStats>,
+    last_state: Option<ServerState>,
 }
 
 #[allow(missing_docs)]
@@ -32,6 +37,8 @@ struct WebSocketImpl<'a, S, L> {
 pub struct WebSocketHandleImpl<'a, S> {
     socket: &'a mut WebSocketStream<S>,
     stop: bool,
+    last_stats: Option<ServerStats>,
+    last_state: Option<ServerState>,
 }
 
 /// An event listener that gets called when websocket messages are received
@@ -59,6 +66,55 @@ pub trait PteroWebSocketListener<H: PteroWebSocketHandle>: Send {
         async { Ok(()) }
     }
 
+    /// Called when an install output message is received
+    fn on_install_output(
+        &mut self,
+        _handle: &mut H,
+        _output: &str,
+    ) -> impl Future<Output = crate::Result<()>> + Send {
+        async { Ok(()) }
+    }
+
+    /// Called when the status of a transfer of the server to another node changes, for example
+    /// to `completed` or `failure`
+    fn on_transfer_status(
+        &mut self,
+        _handle: &mut H,
+        _status: &str,
+    ) -> impl Future<Output = crate::Result<()>> + Send {
+        async { Ok(()) }
+    }
+
+    /// Called when a transfer log message is received while the server is being transferred to
+    /// another node
+    fn on_transfer_logs(
+        &mut self,
+        _handle: &mut H,
+        _output: &str,
+    ) -> impl Future<Output = crate::Result<()>> + Send {
+        async { Ok(()) }
+    }
+
+    /// Called when the websocket token is about to expire, before the websocket is
+    /// re-authenticated with a new token
+    fn on_token_expiring(
+        &mut self,
+        _handle: &mut H,
+    ) -> impl Future<Output = crate::Result<()>> + Send {
+        async { Ok(()) }
+    }
+
+    /// Called when re-authenticating the websocket after the token was about to expire fails. By
+    /// default the error is returned, ending the websocket loop. Listeners may instead return
+    /// `Ok(())` to keep the connection open until the token actually expires
+    fn on_token_refresh_error(
+        &mut self,
+        _handle: &mut H,
+        error: crate::Error,
+    ) -> impl Future<Output = crate::Result<()>> + Send {
+        async { Err(error) }
+    }
+
     /// Called when a server stats message is received
     fn on_stats(
         &mut self,
@@ -67,6 +123,17 @@ pub trait PteroWebSocketListener<H: PteroWebSocketHandle>: Send {
     ) -> impl Future<Output = crate::Result<()>> + Send {
         async { Ok(()) }
     }
+
+    /// Called when a message with an event not otherwise handled by this crate is received, such
+    /// as events added in newer versions of Wings
+    fn on_raw_event(
+        &mut self,
+        _handle: &mut H,
+        _event: &str,
+        _args: &[String],
+    ) -> impl Future<Output = crate::Result<()>> + Send {
+        async { Ok(()) }
+    }
 }
 
 /// A handle to control the websocket
@@ -76,6 +143,10 @@ pub trait PteroWebSocketHandle: Send + sealed::Sealed {
     fn request_stats(&mut self) -> impl Future<Output = crate::Result<()>> + Send;
     /// Request server logs
     fn request_logs(&mut self) -> impl Future<Output = crate::Result<()>> + Send;
+    /// Request the install logs of the server, which are received through
+    /// [`PteroWebSocketListener::on_install_output`]. Requires Wings to support replaying install
+    /// output
+    fn request_install_logs(&mut self) -> impl Future<Output = crate::Result<()>> + Send;
     /// Send a power signal to the server
     fn send_power_signal(
         &mut self,
@@ -86,12 +157,301 @@ pub trait PteroWebSocketHandle: Send + sealed::Sealed {
         &mut self,
         command: impl Into<String> + Send,
     ) -> impl Future<Output = crate::Result<()>> + Send;
-    /// End the websocket connection
+    /// Send an event with the given name and arguments to the server, as
+    /// `{"event": event, "args": args}`. This is intended for events not otherwise supported by
+    /// this crate, such as events added in newer versions of Wings. Events and their arguments are
+    /// not validated, and depend on the version of Wings, so this should be considered unstable
+    fn send_raw_event(
+        &mut self,
+        event: &str,
+        args: Vec<String>,
+    ) -> impl Future<Output = crate::Result<()>> + Send;
+    /// End the websocket connection. The connection is closed cleanly once the current listener
+    /// callback returns
     fn disconnect(&mut self);
+    /// Gets the most recent stats received during this websocket session, including those being
+    /// handled by the current listener callback
+    fn last_stats(&self) -> Option<ServerStats>;
+    /// Gets the most recent state of the server received during this websocket session, from
+    /// either a status or a stats message, including the one being handled by the current listener
+    /// callback
+    fn last_state(&self) -> Option<ServerState>;
+}
+
+impl<H: PteroWebSocketHandle> PteroWebSocketListener<H> for () {}
+
+/// Implements [`PteroWebSocketListener`] for a wrapping listener with an `inner` field, forwarding
+/// every event to `inner` after running `$tap` on console output
+macro_rules! forward_console_tap_listener {
+    ($listener:ident, |$this:ident, $output:ident| $tap:expr) => {
+        impl<H, L> PteroWebSocketListener<H> for $listener<L>
+        where
+            H: PteroWebSocketHandle,
+            L: PteroWebSocketListener<H>,
+        {
+            async fn on_ready(&mut self, handle: &mut H) -> crate::Result<()> {
+                self.inner.on_ready(handle).await
+            }
+
+            async fn on_status(
+                &mut self,
+                handle: &mut H,
+                status: ServerState,
+            ) -> crate::Result<()> {
+                self.inner.on_status(handle, status).await
+            }
+
+            async fn on_console_output(
+                &mut self,
+                handle: &mut H,
+                output: &str,
+            ) -> crate::Result<()> {
+                {
+                    let $this = &mut *self;
+                    let $output = output;
+                    $tap;
+                }
+                self.inner.on_console_output(handle, output).await
+            }
+
+            async fn on_install_output(
+                &mut self,
+                handle: &mut H,
+                output: &str,
+            ) -> crate::Result<()> {
+                self.inner.on_install_output(handle, output).await
+            }
+
+            async fn on_transfer_status(
+                &mut self,
+                handle: &mut H,
+                status: &str,
+            ) -> crate::Result<()> {
+                self.inner.on_transfer_status(handle, status).await
+            }
+
+            async fn on_transfer_logs(
+                &mut self,
+                handle: &mut H,
+                output: &str,
+            ) -> crate::Result<()> {
+                self.inner.on_transfer_logs(handle, output).await
+            }
+
+            async fn on_token_expiring(&mut self, handle: &mut H) -> crate::Result<()> {
+                self.inner.on_token_expiring(handle).await
+            }
+
+            async fn on_token_refresh_error(
+                &mut self,
+                handle: &mut H,
+                error: crate::Error,
+            ) -> crate::Result<()> {
+                self.inner.on_token_refresh_error(handle, error).await
+            }
+
+            async fn on_stats(&mut self, handle: &mut H, stats: ServerStats) -> crate::Result<()> {
+                self.inner.on_stats(handle, stats).await
+            }
+
+            async fn on_raw_event(
+                &mut self,
+                handle: &mut H,
+                event: &str,
+                args: &[String],
+            ) -> crate::Result<()> {
+                self.inner.on_raw_event(handle, event, args).await
+            }
+        }
+    };
+}
+
+/// A listener which keeps the most recent console output lines in a bounded buffer, which can be
+/// read from elsewhere through a [`ConsoleBuffer`] while the websocket loop is running. It can be
+/// used on its own, or wrap another listener to which all events are forwarded
+///
+/// ```no_run
+/// # use pterodactyl_api::client::websocket::RingBufferListener;
+/// # use pterodactyl_api::client::Server;
+/// # let server: Server = todo!();
+/// # async {
+/// let listener = RingBufferListener::new(100);
+/// let buffer = listener.buffer();
+/// # let create = |url: String| async move { async_tungstenite::tokio::connect_async(url).await.map(|(stream, _)| stream) };
+/// let (result, ()) = tokio::join!(server.run_websocket_loop(create, listener), async {
+///     // display buffer.lines() periodically
+/// });
+/// result?;
+/// # Ok::<_, pterodactyl_api::Error>(())
+/// # };
+/// ```
+#[derive(Debug)]
+pub struct RingBufferListener<L = ()> {
+    buffer: ConsoleBuffer,
+    inner: L,
+}
+
+impl RingBufferListener {
+    /// Creates a listener which keeps the last `capacity` console output lines
+    pub fn new(capacity: usize) -> Self {
+        Self::wrap(capacity, ())
+    }
+}
+
+impl<L> RingBufferListener<L> {
+    /// Creates a listener which keeps the last `capacity` console output lines, and forwards all
+    /// events to `inner`
+    pub fn wrap(capacity: usize, inner: L) -> Self {
+        Self {
+            buffer: ConsoleBuffer {
+                lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+                capacity,
+            },
+            inner,
+        }
+    }
+
+    /// Gets a handle to the buffer of console output lines
+    pub fn buffer(&self) -> ConsoleBuffer {
+        self.buffer.clone()
+    }
+
+    /// Gets the wrapped listener
+    pub fn into_inner(self) -> L {
+        self.inner
+    }
+}
+
+forward_console_tap_listener!(RingBufferListener, |this, output| this.buffer.push(output));
+
+/// A shared handle to the console output lines kept by a [`RingBufferListener`]
+#[derive(Debug, Clone)]
+pub struct ConsoleBuffer {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    capacity: usize,
+}
+
+impl ConsoleBuffer {
+    /// Gets a copy of the lines currently in the buffer, oldest first
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Removes and returns the lines currently in the buffer, oldest first
+    pub fn drain(&self) -> Vec<String> {
+        self.lines.lock().unwrap().drain(..).collect()
+    }
+
+    /// The maximum number of lines kept in the buffer
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn push(&self, line: &str) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line.to_owned());
+    }
+}
+
+/// A listener which sends console output lines through a bounded channel, so that they can be
+/// processed elsewhere while the websocket loop is running. It can be used on its own, or wrap
+/// another listener to which all events are forwarded. What happens when the channel is full is
+/// determined by the [`OverflowPolicy`]. To keep only the most recent lines instead, dropping the
+/// oldest ones, use a [`RingBufferListener`]
+///
+/// ```no_run
+/// # use pterodactyl_api::client::websocket::{ChannelListener, OverflowPolicy};
+/// # use pterodactyl_api::client::Server;
+/// # let server: Server = todo!();
+/// # async {
+/// let (listener, mut lines) = ChannelListener::new(100, OverflowPolicy::Backpressure);
+/// # let create = |url: String| async move { async_tungstenite::tokio::connect_async(url).await.map(|(stream, _)| stream) };
+/// let (result, ()) = tokio::join!(server.run_websocket_loop(create, listener), async {
+///     while let Some(line) = lines.recv().await {
+///         println!("{line}");
+///     }
+/// });
+/// result?;
+/// # Ok::<_, pterodactyl_api::Error>(())
+/// # };
+/// ```
+#[derive(Debug)]
+pub struct ChannelListener<L = ()> {
+    sender: tokio::sync::mpsc::Sender<String>,
+    policy: OverflowPolicy,
+    inner: L,
+}
+
+/// What a [`ChannelListener`] does with console output when its channel is full
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[non_exhaustive]
+pub enum OverflowPolicy {
+    /// Wait until there is space in the channel. Since the websocket loop doesn't read the next
+    /// message until the listener has handled the current one, this slows down reading from the
+    /// websocket rather than buffering more messages in memory
+    Backpressure,
+    /// Drop lines which don't fit in the channel, so that the websocket loop is never slowed down
+    DropNewest,
+}
+
+impl ChannelListener {
+    /// Creates a listener which sends console output lines through a channel with the given
+    /// capacity, returning the listener and the receiving end of the channel
+    pub fn new(
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> (Self, tokio::sync::mpsc::Receiver<String>) {
+        Self::wrap(capacity, policy, ())
+    }
+}
+
+impl<L> ChannelListener<L> {
+    /// Creates a listener which sends console output lines through a channel with the given
+    /// capacity, and forwards all events to `inner`. Returns the listener and the receiving end of
+    /// the channel. Panics if `capacity` is zero
+    pub fn wrap(
+        capacity: usize,
+        policy: OverflowPolicy,
+        inner: L,
+    ) -> (Self, tokio::sync::mpsc::Receiver<String>) {
+        let (sender, receiver) = tokio::sync::mpsc::channel(capacity);
+        (
+            Self {
+                sender,
+                policy,
+                inner,
+            },
+            receiver,
+        )
+    }
+
+    /// Gets the wrapped listener
+    pub fn into_inner(self) -> L {
+        self.inner
+    }
 }
 
+forward_console_tap_listener!(ChannelListener, |this, output| {
+    // errors mean that the receiver was dropped or the line doesn't fit, in which case the line is
+    // dropped
+    match this.policy {
+        OverflowPolicy::Backpressure => {
+            let _ = this.sender.send(output.to_owned()).await;
+        }
+        OverflowPolicy::DropNewest => {
+            let _ = this.sender.try_send(output.to_owned());
+        }
+    }
+});
+
 /// Server stats received from a websocket
-#[derive(Debug, Deserialize, Copy, Clone)]
+#[derive(Debug, Deserialize, PartialEq, Copy, Clone)]
 #[non_exhaustive]
 pub struct ServerStats {
     /// The used memory of the server in bytes
@@ -109,7 +469,7 @@ pub struct ServerStats {
 }
 
 /// Server network stats received from a websocket
-#[derive(Debug, Deserialize, Copy, Clone)]
+#[derive(Debug, Deserialize, PartialEq, Eq, Copy, Clone)]
 pub struct ServerNetworkStats {
     /// Number of bytes received
     pub rx_bytes: u64,
@@ -117,6 +477,56 @@ pub struct ServerNetworkStats {
     pub tx_bytes: u64,
 }
 
+/// The reason the websocket loop ended without an error
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[non_exhaustive]
+pub enum WebSocketExit {
+    /// A listener called [`PteroWebSocketHandle::disconnect`]
+    Disconnected,
+    /// The connection was closed by Wings
+    Closed,
+    /// The websocket token expired without being refreshed, so Wings will no longer send events.
+    /// Reconnecting will fetch a new token
+    TokenExpired,
+}
+
+/// Options for the websocket loop
+#[derive(Debug, Default, Clone)]
+pub struct WebSocketOptions {
+    stats_interval: Option<Duration>,
+    replay_logs_on_connect: bool,
+    // when set, the loop closes the connection and returns a timeout error at this instant
+    deadline: Option<tokio::time::Instant>,
+}
+
+impl WebSocketOptions {
+    /// Creates the default websocket options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests server stats every `interval` once the websocket is ready, which are received
+    /// through [`PteroWebSocketListener::on_stats`]. If a listener callback takes longer than the
+    /// interval, missed requests are not made up for, so the socket is never flooded
+    pub fn with_stats_interval(self, interval: Duration) -> Self {
+        Self {
+            stats_interval: Some(interval),
+            ..self
+        }
+    }
+
+    /// Requests the recent console output of the server right after
+    /// [`PteroWebSocketListener::on_ready`] has been called, so that the listener receives it
+    /// through [`PteroWebSocketListener::on_console_output`] without having to call
+    /// [`PteroWebSocketHandle::request_logs`] itself
+    pub fn set_replay_logs_on_connect(self) -> Self {
+        Self {
+            replay_logs_on_connect: true,
+            ..self
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct WebSocketLink {
     token: String,
@@ -125,28 +535,191 @@ struct WebSocketLink {
 
 impl<'a> Server<'a> {
     /// Runs the websocket loop until the websocket is disconnected. Takes a function which creates
-    /// a websocket stream from a websocket URL, and an event listener
+    /// a websocket stream from a websocket URL, and an event listener. Returns why the loop ended,
+    /// which callers can use to decide whether to reconnect.
+    ///
+    /// The next message is only read from the websocket once the listener callbacks for the
+    /// previous message have returned, so a slow listener applies backpressure to the connection
+    /// rather than causing messages to be buffered in memory. To process console output elsewhere
+    /// with bounded buffering, use a [`ChannelListener`] or a [`RingBufferListener`]
     pub async fn run_websocket_loop<S, F, L>(
         &self,
         create: impl FnOnce(String) -> F,
         listener: L,
-    ) -> crate::Result<()>
+    ) -> crate::Result<WebSocketExit>
+    where
+        F: Future<Output = async_tungstenite::tungstenite::Result<WebSocketStream<S>>>,
+        L: for<'b> PteroWebSocketListener<WebSocketHandleImpl<'b, S>>,
+        S: AsyncRead + AsyncWrite + Unpin + Send,
+    {
+        self.run_websocket_loop_with_options(create, listener, WebSocketOptions::default())
+            .await
+    }
+
+    /// Runs the websocket loop until the websocket is disconnected, with the given options. See
+    /// [`Server::run_websocket_loop`]
+    pub async fn run_websocket_loop_with_options<S, F, L>(
+        &self,
+        create: impl FnOnce(String) -> F,
+        listener: L,
+        options: WebSocketOptions,
+    ) -> crate::Result<WebSocketExit>
     where
         F: Future<Output = async_tungstenite::tungstenite::Result<WebSocketStream<S>>>,
         L: for<'b> PteroWebSocketListener<WebSocketHandleImpl<'b, S>>,
         S: AsyncRead + AsyncWrite + Unpin + Send,
     {
-        let WebSocketLink { token, socket: url } = self.get_websocket_link().await?;
-        let socket = create(url).await?;
+        let connect = async {
+            let WebSocketLink { token, socket: url } = self.get_websocket_link().await?;
+            Ok::<_, crate::Error>((token, create(url).await?))
+        };
+        let (token, socket) = match options.deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, connect)
+                .await
+                .map_err(|_elapsed| crate::Error::Timeout)??,
+            None => connect.await?,
+        };
         let socket = WebSocketImpl {
             server: self,
             socket,
             listener,
+            options,
             ready: false,
+            last_stats: None,
+            last_state: None,
         };
         socket.run_loop(token).await
     }
 
+    /// Connects to the websocket of this server using tokio and native TLS, and runs the websocket
+    /// loop until the websocket is disconnected. See [`Server::run_websocket_loop`]
+    #[cfg(feature = "websocket-native-tls")]
+    pub async fn connect_websocket<L>(&self, listener: L) -> crate::Result<WebSocketExit>
+    where
+        L: for<'b> PteroWebSocketListener<
+            WebSocketHandleImpl<'b, async_tungstenite::tokio::ConnectStream>,
+        >,
+    {
+        self.connect_websocket_with_tls_connector(listener, None)
+            .await
+    }
+
+    /// Connects to the websocket of this server using tokio and the given TLS connector, and runs
+    /// the websocket loop until the websocket is disconnected. This allows the websocket to use the
+    /// same TLS configuration as the [`reqwest::Client`] given to
+    /// [`ClientBuilder::with_client`](crate::client::ClientBuilder::with_client), such as custom
+    /// root certificates for panels using an internal CA. If `connector` is `None`, a default
+    /// connector is used. See [`Server::run_websocket_loop`]
+    #[cfg(feature = "websocket-native-tls")]
+    pub async fn connect_websocket_with_tls_connector<L>(
+        &self,
+        listener: L,
+        connector: Option<native_tls::TlsConnector>,
+    ) -> crate::Result<WebSocketExit>
+    where
+        L: for<'b> PteroWebSocketListener<
+            WebSocketHandleImpl<'b, async_tungstenite::tokio::ConnectStream>,
+        >,
+    {
+        use async_tungstenite::tungstenite::client::IntoClientRequest;
+
+        // Wings rejects connections which don't come from the panel's origin
+        let origin = reqwest::Url::parse(&self.client.url)
+            .map(|url| url.origin().ascii_serialization())
+            .unwrap_or_default();
+        self.run_websocket_loop(
+            |url| async move {
+                let mut request = url.into_client_request()?;
+                request.headers_mut().insert("Origin", origin.parse()?);
+                let (stream, _) = async_tungstenite::tokio::connect_async_with_tls_connector(
+                    request,
+                    connector.map(tokio_native_tls::TlsConnector::from),
+                )
+                .await?;
+                Ok(stream)
+            },
+            listener,
+        )
+        .await
+    }
+
+    /// Waits until the state of this server is `target` using the websocket. Unlike
+    /// [`Server::wait_for_state`], this does not repeatedly poll the API. Takes a function which
+    /// creates a websocket stream from a websocket URL, as in [`Server::run_websocket_loop`].
+    /// Returns [`crate::Error::Timeout`] if the server didn't reach that state within `timeout`,
+    /// in which case the websocket is closed cleanly. If the websocket is closed by Wings before
+    /// then, the API is polled every second for the rest of the timeout instead
+    pub async fn wait_for_state_with_websocket<S, F>(
+        &self,
+        create: impl FnOnce(String) -> F,
+        target: ServerState,
+        timeout: Duration,
+    ) -> crate::Result<()>
+    where
+        F: Future<Output = async_tungstenite::tungstenite::Result<WebSocketStream<S>>>,
+        S: AsyncRead + AsyncWrite + Unpin + Send,
+    {
+        struct WaitForStateListener<'a> {
+            target: ServerState,
+            reached: &'a mut bool,
+        }
+        impl WaitForStateListener<'_> {
+            fn update_state<H: PteroWebSocketHandle>(
+                &mut self,
+                handle: &mut H,
+                state: ServerState,
+            ) {
+                if state == self.target {
+                    *self.reached = true;
+                    handle.disconnect();
+                }
+            }
+        }
+        impl<H: PteroWebSocketHandle> PteroWebSocketListener<H> for WaitForStateListener<'_> {
+            async fn on_ready(&mut self, handle: &mut H) -> crate::Result<()> {
+                handle.request_stats().await
+            }
+
+            async fn on_status(
+                &mut self,
+                handle: &mut H,
+                status: ServerState,
+            ) -> crate::Result<()> {
+                self.update_state(handle, status);
+                Ok(())
+            }
+
+            async fn on_stats(&mut self, handle: &mut H, stats: ServerStats) -> crate::Result<()> {
+                self.update_state(handle, stats.state);
+                Ok(())
+            }
+        }
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut reached = false;
+        self.run_websocket_loop_with_options(
+            create,
+            WaitForStateListener {
+                target,
+                reached: &mut reached,
+            },
+            WebSocketOptions {
+                deadline: Some(deadline),
+                ..WebSocketOptions::default()
+            },
+        )
+        .await?;
+        if reached {
+            return Ok(());
+        }
+        self.wait_for_state(
+            target,
+            deadline.saturating_duration_since(tokio::time::Instant::now()),
+            Duration::from_secs(1),
+        )
+        .await
+    }
+
     async fn get_websocket_link(&self) -> crate::Result<WebSocketLink> {
         #[derive(Deserialize)]
         struct Data {
@@ -168,8 +741,14 @@ enum IncomingEvent {
     Status,
     #[serde(rename = "console output")]
     ConsoleOutput,
+    #[serde(rename = "install output")]
+    InstallOutput,
     #[serde(rename = "stats")]
     Stats,
+    #[serde(rename = "transfer status")]
+    TransferStatus,
+    #[serde(rename = "transfer logs")]
+    TransferLogs,
     #[serde(rename = "token expiring")]
     TokenExpiring,
     #[serde(rename = "token expired")]
@@ -183,97 +762,227 @@ where
     S: AsyncRead + AsyncWrite + Unpin + Send,
     L: for<'b> PteroWebSocketListener<WebSocketHandleImpl<'b, S>>,
 {
-    async fn run_loop(mut self, token: String) -> crate::Result<()> {
+    async fn run_loop(mut self, token: String) -> crate::Result<WebSocketExit> {
+        let result = match self.options.deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, self.process_messages(token))
+                .await
+                .unwrap_or(Err(crate::Error::Timeout)),
+            None => self.process_messages(token).await,
+        };
+        // Close the connection cleanly so Wings doesn't have to wait for it to time out. Errors
+        // here are ignored so that they don't mask the result of the loop, and will occur anyway if
+        // the connection was already closed.
+        let _ = self.socket.close(None).await;
+        result
+    }
+
+    async fn process_messages(&mut self, token: String) -> crate::Result<WebSocketExit> {
         self.auth(token).await?;
-        while let Some(message) = self.socket.next().await {
-            if let Message::Text(message) = message? {
-                if self.handle_message(message).await? {
-                    break;
+        let mut stats_interval = self.options.stats_interval.map(|interval| {
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            interval
+        });
+        loop {
+            let message = match &mut stats_interval {
+                Some(interval) if self.ready => tokio::select! {
+                    message = self.socket.next() => message,
+                    _ = interval.tick() => {
+                        let mut handle = WebSocketHandleImpl {
+                            socket: &mut self.socket,
+                            stop: false,
+                            last_stats: self.last_stats,
+                            last_state: self.last_state,
+                        };
+                        handle.request_stats().await?;
+                        continue;
+                    }
+                },
+                _ => self.socket.next().await,
+            };
+            let Some(message) = message else {
+                return Ok(WebSocketExit::Closed);
+            };
+            match message? {
+                Message::Text(message) => {
+                    if let Some(exit) = self.handle_message(message).await? {
+                        return Ok(exit);
+                    }
                 }
-            } else {
-                return Err(crate::Error::UnexpectedMessage);
+                Message::Ping(data) => self.socket.send(Message::Pong(data)).await?,
+                Message::Close(_) => return Ok(WebSocketExit::Closed),
+                // Wings doesn't send binary messages, so ignore any stray ones rather than killing
+                // the connection
+                Message::Pong(_) | Message::Binary(_) | Message::Frame(_) => {}
             }
         }
-        Ok(())
     }
 
-    async fn handle_message(&mut self, message: String) -> crate::Result<bool> {
+    async fn handle_message(&mut self, message: String) -> crate::Result<Option<WebSocketExit>> {
         #[derive(Deserialize)]
         struct Message {
-            event: IncomingEvent,
+            event: String,
             #[serde(default)]
             args: Vec<String>,
         }
         let message: Message = serde_json::from_str(&message)?;
-        if message.event != IncomingEvent::AuthSuccess && !self.ready {
+        let event =
+            IncomingEvent::deserialize(StrDeserializer::<serde_json::Error>::new(&message.event))?;
+        if event != IncomingEvent::AuthSuccess && !self.ready {
             return Err(crate::Error::UnexpectedMessage);
         }
 
-        match message.event {
+        match event {
             IncomingEvent::AuthSuccess => {
                 let mut handle = WebSocketHandleImpl {
                     socket: &mut self.socket,
                     stop: false,
+                    last_stats: self.last_stats,
+                    last_state: self.last_state,
                 };
                 if !self.ready {
                     self.ready = true;
                     self.listener.on_ready(&mut handle).await?;
+                    if self.options.replay_logs_on_connect && !handle.stop {
+                        handle.request_logs().await?;
+                    }
                 }
-                Ok(handle.stop)
+                Ok(handle.exit())
             }
             IncomingEvent::Status => {
+                let status = ServerState::deserialize(StrDeserializer::<serde_json::Error>::new(
+                    message
+                        .args
+                        .first()
+                        .ok_or(crate::Error::UnexpectedMessage)?,
+                ))?;
+                self.last_state = Some(status);
                 let mut handle = WebSocketHandleImpl {
                     socket: &mut self.socket,
                     stop: false,
+                    last_stats: self.last_stats,
+                    last_state: self.last_state,
                 };
-                self.listener
-                    .on_status(
-                        &mut handle,
-                        ServerState::deserialize(StrDeserializer::<serde_json::Error>::new(
-                            message
-                                .args
-                                .first()
-                                .ok_or(crate::Error::UnexpectedMessage)?,
-                        ))?,
-                    )
-                    .await?;
-                Ok(handle.stop)
+                self.listener.on_status(&mut handle, status).await?;
+                Ok(handle.exit())
             }
             IncomingEvent::ConsoleOutput => {
                 let mut handle = WebSocketHandleImpl {
                     socket: &mut self.socket,
                     stop: false,
+                    last_stats: self.last_stats,
+                    last_state: self.last_state,
                 };
                 for output in message.args {
                     self.listener
                         .on_console_output(&mut handle, &output)
                         .await?;
                 }
-                Ok(handle.stop)
+                Ok(handle.exit())
             }
-            IncomingEvent::Stats => {
+            IncomingEvent::InstallOutput => {
                 let mut handle = WebSocketHandleImpl {
                     socket: &mut self.socket,
                     stop: false,
+                    last_stats: self.last_stats,
+                    last_state: self.last_state,
                 };
+                for output in message.args {
+                    self.listener
+                        .on_install_output(&mut handle, &output)
+                        .await?;
+                }
+                Ok(handle.exit())
+            }
+            IncomingEvent::Stats => {
                 let json = message
                     .args
                     .first()
                     .ok_or(crate::Error::UnexpectedMessage)?;
                 let stats: ServerStats = serde_json::from_str(json)?;
+                self.last_stats = Some(stats);
+                self.last_state = Some(stats.state);
+                let mut handle = WebSocketHandleImpl {
+                    socket: &mut self.socket,
+                    stop: false,
+                    last_stats: self.last_stats,
+                    last_state: self.last_state,
+                };
                 self.listener.on_stats(&mut handle, stats).await?;
-                Ok(handle.stop)
+                Ok(handle.exit())
+            }
+            IncomingEvent::TransferStatus => {
+                let mut handle = WebSocketHandleImpl {
+                    socket: &mut self.socket,
+                    stop: false,
+                    last_stats: self.last_stats,
+                    last_state: self.last_state,
+                };
+                let status = message
+                    .args
+                    .first()
+                    .ok_or(crate::Error::UnexpectedMessage)?;
+                self.listener
+                    .on_transfer_status(&mut handle, status)
+                    .await?;
+                Ok(handle.exit())
+            }
+            IncomingEvent::TransferLogs => {
+                let mut handle = WebSocketHandleImpl {
+                    socket: &mut self.socket,
+                    stop: false,
+                    last_stats: self.last_stats,
+                    last_state: self.last_state,
+                };
+                for output in message.args {
+                    self.listener.on_transfer_logs(&mut handle, &output).await?;
+                }
+                Ok(handle.exit())
             }
             IncomingEvent::TokenExpiring => {
-                self.auth(self.server.get_websocket_link().await?.token)
+                let mut handle = WebSocketHandleImpl {
+                    socket: &mut self.socket,
+                    stop: false,
+                    last_stats: self.last_stats,
+                    last_state: self.last_state,
+                };
+                self.listener.on_token_expiring(&mut handle).await?;
+                let mut stop = handle.stop;
+                if let Err(err) = self.reauth().await {
+                    let mut handle = WebSocketHandleImpl {
+                        socket: &mut self.socket,
+                        stop: false,
+                        last_stats: self.last_stats,
+                        last_state: self.last_state,
+                    };
+                    self.listener
+                        .on_token_refresh_error(&mut handle, err)
+                        .await?;
+                    stop |= handle.stop;
+                }
+                Ok(stop.then_some(WebSocketExit::Disconnected))
+            }
+            IncomingEvent::TokenExpired => Ok(Some(WebSocketExit::TokenExpired)),
+            IncomingEvent::Other => {
+                let mut handle = WebSocketHandleImpl {
+                    socket: &mut self.socket,
+                    stop: false,
+                    last_stats: self.last_stats,
+                    last_state: self.last_state,
+                };
+                self.listener
+                    .on_raw_event(&mut handle, &message.event, &message.args)
                     .await?;
-                Ok(false)
+                Ok(handle.exit())
             }
-            IncomingEvent::TokenExpired => Err(WebsocketTokenExpired),
-            IncomingEvent::Other => Ok(false),
         }
     }
 
+    async fn reauth(&mut self) -> crate::Result<()> {
+        self.auth(self.server.get_websocket_link().await?.token)
+            .await
+    }
+
     async fn auth(&mut self, token: String) -> crate::Result<()> {
         #[derive(Serialize)]
         struct AuthEvent {
@@ -288,6 +997,12 @@ where
     }
 }
 
+impl<S> WebSocketHandleImpl<'_, S> {
+    fn exit(&self) -> Option<WebSocketExit> {
+        self.stop.then_some(WebSocketExit::Disconnected)
+    }
+}
+
 impl<S> PteroWebSocketHandle for WebSocketHandleImpl<'_, S>
 where
     S: AsyncRead + AsyncWrite + Unpin + Send,
@@ -306,6 +1021,15 @@ where
             .await?)
     }
 
+    async fn request_install_logs(&mut self) -> crate::Result<()> {
+        Ok(self
+            .socket
+            .send(Message::text(
+                "{\"event\":\"send install logs\",\"args\":[null]}",
+            ))
+            .await?)
+    }
+
     async fn send_power_signal(&mut self, signal: PowerSignal) -> crate::Result<()> {
         #[derive(Serialize)]
         struct PowerSignalEvent {
@@ -332,7 +1056,25 @@ where
         Ok(self.socket.send(Message::text(payload)).await?)
     }
 
+    async fn send_raw_event(&mut self, event: &str, args: Vec<String>) -> crate::Result<()> {
+        #[derive(Serialize)]
+        struct RawEvent<'a> {
+            event: &'a str,
+            args: Vec<String>,
+        }
+        let payload = serde_json::to_string(&RawEvent { event, args })?;
+        Ok(self.socket.send(Message::text(payload)).await?)
+    }
+
     fn disconnect(&mut self) {
         self.stop = true;
     }
+
+    fn last_stats(&self) -> Option<ServerStats> {
+        self.last_stats
+    }
+
+    fn last_state(&self) -> Option<ServerState> {
+        self.last_state
+    }
 }
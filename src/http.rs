@@ -1,4 +1,8 @@
-use crate::client::{Client, RateLimits};
+use crate::client::{CachedResponse, Client, ErrorResponse, RateLimits};
+use bytes::Bytes;
+#[cfg(feature = "stream")]
+use reqwest::header::CONTENT_LENGTH;
+use reqwest::header::{HeaderMap, CONTENT_TYPE, ETAG, IF_NONE_MATCH};
 use reqwest::{Body, Method, RequestBuilder, Response, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -49,23 +53,102 @@ impl Client {
         endpoint: &str,
         body: Body,
     ) -> crate::Result<Response> {
-        let request = self
+        let url = format!("{}{}", self.url, endpoint);
+        let etag_cache = self.etag_cache.as_ref().filter(|_| method == Method::GET);
+        let mut request = self
             .client
-            .request(method, format!("{}{}", self.url, endpoint))
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/json")
-            .header("Authorization", format!("Bearer {}", self.api_key));
+            .request(method, &url)
+            .header("Accept", "application/json");
+        if let Some(api_key) = &self.api_key {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+        if let Some(cached) = etag_cache.and_then(|cache| {
+            cache
+                .read()
+                .unwrap()
+                .get(&url)
+                .map(|cached| cached.etag.clone())
+        }) {
+            request = request.header(IF_NONE_MATCH, cached);
+        }
         let request = body.encode(request)?;
+        // if the semaphore has been closed, don't limit the request
+        let permit = match &self.concurrency_limit {
+            Some(semaphore) => semaphore.acquire().await.ok(),
+            None => None,
+        };
+        let start = self
+            .on_request_complete
+            .as_ref()
+            .map(|_| std::time::Instant::now());
         let response = request.send().await?;
+        drop(permit);
+        if let (Some(callback), Some(start)) = (&self.on_request_complete, start) {
+            (callback.0)(endpoint, response.status(), start.elapsed());
+        }
+
+        // record the rate limits regardless of the status, since they are most useful after a
+        // request was rate limited
+        self.update_rate_limits(&response);
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cache) = etag_cache {
+                if let Some(cached) = cache.read().unwrap().get(&url) {
+                    return Ok(cached_response(cached.headers.clone(), cached.body.clone()));
+                }
+            }
+        }
 
         if !response.status().is_success() {
             let status = response.status();
+            // any operation on a suspended server is rejected with the same error, so check for it
+            // before the endpoint-specific error handler
+            let response = if status == StatusCode::CONFLICT {
+                let headers = response.headers().clone();
+                let body = response.bytes().await?;
+                if serde_json::from_slice::<ErrorResponse>(&body)
+                    .is_ok_and(|error| error.is_suspended())
+                {
+                    return Err(crate::Error::ServerSuspended);
+                }
+                let mut response = http::Response::new(body);
+                *response.status_mut() = status;
+                *response.headers_mut() = headers;
+                Response::from(response)
+            } else {
+                response
+            };
             if let Some(err) = EHandler::get_error(response).await {
                 return Err(err);
             }
-            return Err(Self::translate_error(status));
+            return Err(Self::translate_error(status, endpoint));
         }
 
+        if let Some(cache) = etag_cache {
+            let is_json = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|header| header.to_str().ok())
+                .is_some_and(|header| header.starts_with("application/json"));
+            if let Some(etag) = response.headers().get(ETAG).filter(|_| is_json).cloned() {
+                let headers = response.headers().clone();
+                let body = response.bytes().await?;
+                cache.write().unwrap().insert(
+                    url,
+                    CachedResponse {
+                        etag,
+                        headers: headers.clone(),
+                        body: body.clone(),
+                    },
+                );
+                return Ok(cached_response(headers, body));
+            }
+        }
+
+        Ok(response)
+    }
+
+    fn update_rate_limits(&self, response: &Response) {
         if let Some(limit) = response
             .headers()
             .get("x-ratelimit-limit")
@@ -84,20 +167,21 @@ impl Client {
                 });
             }
         }
-
-        Ok(response)
     }
 
-    fn translate_error(status: StatusCode) -> crate::Error {
+    fn translate_error(status: StatusCode, endpoint: &str) -> crate::Error {
         match status {
             StatusCode::FORBIDDEN => crate::Error::PermissionError,
-            StatusCode::NOT_FOUND => crate::Error::ResourceNotFound,
+            StatusCode::NOT_FOUND => crate::Error::ResourceNotFound {
+                endpoint: endpoint.to_owned(),
+            },
             StatusCode::TOO_MANY_REQUESTS => crate::Error::RateLimit,
             status => crate::Error::Http(status),
         }
     }
 
     #[cfg(test)]
+    #[allow(dead_code)]
     pub(crate) async fn dump_response<Body: RequestBody>(
         &self,
         method: Method,
@@ -112,6 +196,27 @@ impl Client {
     }
 }
 
+pub(crate) fn api_url(base_url: &str, api_prefix: &str) -> String {
+    let mut url = base_url.to_owned();
+    if !url.ends_with('/') {
+        url.push('/');
+    }
+    let api_prefix = api_prefix.trim_start_matches('/');
+    if !api_prefix.is_empty() {
+        url.push_str(api_prefix);
+        if !url.ends_with('/') {
+            url.push('/');
+        }
+    }
+    url
+}
+
+fn cached_response(headers: HeaderMap, body: Bytes) -> Response {
+    let mut response = http::Response::new(body);
+    *response.headers_mut() = headers;
+    Response::from(response)
+}
+
 pub(crate) trait RequestBody {
     fn encode(self, request: RequestBuilder) -> crate::Result<RequestBuilder>;
 }
@@ -136,24 +241,62 @@ impl ResponseBody for EmptyBody {
 
 impl<T: Serialize> RequestBody for &T {
     fn encode(self, request: RequestBuilder) -> crate::Result<RequestBuilder> {
-        Ok(request.body(serde_json::to_string(self)?))
+        Ok(request
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_string(self)?))
     }
 }
 
 impl<T: DeserializeOwned> ResponseBody for T {
     async fn decode(response: Response) -> crate::Result<Self> {
         let bytes = response.bytes().await?;
+        // some panel versions respond with 204 No Content where a JSON body is usually sent, which
+        // is only valid for types that can be deserialized from null
+        if bytes.iter().all(u8::is_ascii_whitespace) {
+            return serde_json::from_slice(b"null")
+                .ok()
+                .ok_or(crate::Error::EmptyResponse);
+        }
         Ok(serde_json::from_slice(&bytes)?)
     }
 }
 
-pub(crate) struct RawBody<T>(pub(crate) T);
-impl<T> RequestBody for RawBody<T>
+pub(crate) struct RawBody<'a, T>(pub(crate) T, pub(crate) &'a str);
+impl<T> RequestBody for RawBody<'_, T>
 where
     T: Into<Body>,
 {
     fn encode(self, request: RequestBuilder) -> crate::Result<RequestBuilder> {
-        Ok(request.body(self.0))
+        Ok(request.header(CONTENT_TYPE, self.1).body(self.0))
+    }
+}
+
+#[cfg(feature = "stream")]
+pub(crate) struct SizedBody<T>(pub(crate) T, pub(crate) u64);
+#[cfg(feature = "stream")]
+impl<T> RequestBody for SizedBody<T>
+where
+    T: Into<Body>,
+{
+    fn encode(self, request: RequestBuilder) -> crate::Result<RequestBuilder> {
+        Ok(request
+            .header(CONTENT_TYPE, "application/octet-stream")
+            .header(CONTENT_LENGTH, self.1)
+            .body(self.0))
+    }
+}
+
+pub(crate) struct WithHeader<B>(
+    pub(crate) B,
+    pub(crate) reqwest::header::HeaderName,
+    pub(crate) String,
+);
+impl<B> RequestBody for WithHeader<B>
+where
+    B: RequestBody,
+{
+    fn encode(self, request: RequestBuilder) -> crate::Result<RequestBuilder> {
+        self.0.encode(request.header(self.1, self.2))
     }
 }
 
@@ -167,3 +310,136 @@ impl ErrorHandler for NullErrorHandler {
         None
     }
 }
+
+/// Starts a mock panel which answers one connection with each of the given responses in turn,
+/// sending the body as JSON unless it is empty. Returns the URL of the mock panel, and a handle
+/// which resolves to the raw requests it received once all responses have been sent
+#[cfg(test)]
+pub(crate) async fn mock_server(
+    responses: Vec<(StatusCode, String)>,
+) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn is_complete(request: &[u8]) -> bool {
+        let Some(header_end) = request.windows(4).position(|w| w == b"\r\n\r\n") else {
+            return false;
+        };
+        let headers = String::from_utf8_lossy(&request[..header_end]).to_ascii_lowercase();
+        let body = &request[header_end + 4..];
+        if headers.contains("transfer-encoding: chunked") {
+            return body.ends_with(b"0\r\n\r\n");
+        }
+        let content_length = headers
+            .lines()
+            .find_map(|line| line.strip_prefix("content-length:"))
+            .and_then(|length| length.trim().parse().ok())
+            .unwrap_or(0);
+        body.len() >= content_length
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = tokio::spawn(async move {
+        let mut requests = Vec::new();
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            while !is_complete(&request) {
+                let mut chunk = [0; 4096];
+                let len = stream.read(&mut chunk).await.unwrap();
+                assert_ne!(len, 0, "connection closed before the request was complete");
+                request.extend_from_slice(&chunk[..len]);
+            }
+            requests.push(String::from_utf8_lossy(&request).into_owned());
+
+            let mut response = format!(
+                "HTTP/1.1 {} {}\r\nConnection: close\r\n",
+                status.as_u16(),
+                status.canonical_reason().unwrap_or_default()
+            );
+            if !body.is_empty() {
+                response.push_str("Content-Type: application/json\r\n");
+            }
+            if status != StatusCode::NO_CONTENT {
+                response.push_str(&format!("Content-Length: {}\r\n", body.len()));
+            }
+            response.push_str("\r\n");
+            response.push_str(&body);
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+        requests
+    });
+    (url, handle)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::http::{cached_response, mock_server, ResponseBody};
+    use bytes::Bytes;
+    use reqwest::header::HeaderMap;
+
+    #[tokio::test]
+    async fn test_decode_empty_body() {
+        let empty = || cached_response(HeaderMap::new(), Bytes::new());
+        assert!(matches!(
+            Vec::<String>::decode(empty()).await,
+            Err(crate::Error::EmptyResponse)
+        ));
+        assert_eq!(Option::<Vec<String>>::decode(empty()).await.unwrap(), None);
+        <()>::decode(empty()).await.unwrap();
+        assert_eq!(
+            Vec::<String>::decode(cached_response(HeaderMap::new(), Bytes::from("[]")))
+                .await
+                .unwrap(),
+            Vec::<String>::new()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_server_suspended() {
+        use crate::client::{ClientBuilder, PowerSignal, ServerState};
+        use reqwest::StatusCode;
+
+        let error = |detail: &str| {
+            serde_json::json!({"errors": [{
+                "code": "ServerStateConflictException",
+                "status": "409",
+                "detail": detail,
+            }]})
+            .to_string()
+        };
+        let (url, _) = mock_server(vec![
+            (
+                StatusCode::CONFLICT,
+                error("This server is currently suspended and the functionality requested is unavailable."),
+            ),
+            (
+                StatusCode::CONFLICT,
+                error("Cannot start server: server is already Running."),
+            ),
+            (
+                StatusCode::CONFLICT,
+                error("This server has not yet completed its installation process, please try again later."),
+            ),
+        ])
+        .await;
+
+        let client = ClientBuilder::new(url, "test-key").build();
+        let server = client.get_server("1");
+        assert!(matches!(
+            server.send_power_signal(PowerSignal::Start).await,
+            Err(crate::Error::ServerSuspended)
+        ));
+        // other conflicts still reach the endpoint-specific error handler
+        assert!(matches!(
+            server.send_power_signal(PowerSignal::Start).await,
+            Err(crate::Error::InvalidPowerState {
+                current: Some(ServerState::Running)
+            })
+        ));
+        assert!(matches!(
+            server.send_power_signal(PowerSignal::Start).await,
+            Err(crate::Error::Http(StatusCode::CONFLICT))
+        ));
+    }
+}
@@ -12,7 +12,7 @@
     clippy::dbg_macro,
     clippy::debug_assert_with_mut_call,
     clippy::doc_markdown,
-    clippy::empty_enum,
+    clippy::empty_enums,
     clippy::enum_glob_use,
     clippy::exit,
     clippy::expl_impl_clone_on_copy,
@@ -40,12 +40,10 @@
     clippy::map_err_ignore,
     clippy::map_flatten,
     clippy::map_unwrap_or,
-    clippy::match_on_vec_items,
     clippy::match_same_arms,
     clippy::match_wild_err_arm,
     clippy::match_wildcard_for_single_variants,
     clippy::mem_forget,
-    clippy::mismatched_target_os,
     clippy::missing_enforced_import_renames,
     clippy::mut_mut,
     clippy::mutex_integer,
@@ -64,7 +62,6 @@
     clippy::string_add_assign,
     clippy::string_add,
     clippy::string_lit_as_bytes,
-    clippy::string_to_string,
     clippy::todo,
     clippy::trait_duplication_in_bounds,
     clippy::unimplemented,
@@ -79,10 +76,13 @@
 )]
 // END - Embark standard lints v6 for Rust 1.55+
 // crate-specific exceptions:
-// #![allow()]
+#![allow(clippy::result_large_err)]
 #![deny(missing_debug_implementations)]
 #![deny(missing_docs)]
 
+pub mod application;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod client;
 mod http;
 mod structs;
@@ -104,6 +104,14 @@ pub enum Error {
     #[error("Json Error: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// I/O errors
+    #[error("I/O Error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Text was expected to be UTF-8, but was not
+    #[error("Invalid UTF-8: {0}")]
+    InvalidUtf8(#[from] std::string::FromUtf8Error),
+
     /// Miscellaneous HTTP status codes
     #[error("Http Status Code: {0}")]
     Http(StatusCode),
@@ -118,8 +126,10 @@ pub enum Error {
     #[error("Unexpected Message")]
     UnexpectedMessage,
 
-    /// The websocket token expired
+    /// The websocket token expired. This is no longer returned, since the websocket loop now
+    /// returns [`client::websocket::WebSocketExit::TokenExpired`] instead
     #[cfg(feature = "websocket")]
+    #[deprecated(note = "the websocket loop returns `WebSocketExit::TokenExpired` instead")]
     #[error("WebSocket Token Expired")]
     WebsocketTokenExpired,
 
@@ -144,10 +154,183 @@ pub enum Error {
     InvalidEmail,
 
     /// The requested resource was not found
-    #[error("Resource Not Found")]
-    ResourceNotFound,
+    #[error("Resource Not Found: {endpoint}")]
+    ResourceNotFound {
+        /// The endpoint which was requested, relative to the API URL, which indicates which
+        /// resource was missing
+        endpoint: String,
+    },
 
     /// Unable to delete the primary network allocation
     #[error("Primary Allocation")]
     PrimaryAllocation,
+
+    /// The server must be running to perform this operation
+    #[error("Server Not Running")]
+    ServerNotRunning,
+
+    /// The server is suspended, so the panel rejected the operation. Whether a server is
+    /// suspended can also be checked with [`crate::client::ServerResources::is_suspended`]
+    #[error("Server Suspended")]
+    ServerSuspended,
+
+    /// The server is already in the state a power signal would put it in, for example starting
+    /// an already running server
+    #[error("Invalid Power State")]
+    InvalidPowerState {
+        /// The current state of the server, as reported by Wings
+        current: Option<crate::client::ServerState>,
+    },
+
+    /// The panel rejected the value of a startup variable
+    #[error("Variable Validation Failed: {}", .messages.join(", "))]
+    VariableValidation {
+        /// The validation messages from the panel, explaining why the value was rejected
+        messages: Vec<String>,
+    },
+
+    /// A file already exists at the destination
+    #[error("File Exists")]
+    FileExists,
+
+    /// Downloaded data didn't match its expected checksum
+    #[cfg(feature = "checksum")]
+    #[error("Checksum Mismatch")]
+    ChecksumMismatch,
+
+    /// An operation was cancelled by a [`tokio_util::sync::CancellationToken`], see [`cancellable`]
+    #[cfg(feature = "cancellation")]
+    #[error("Cancelled")]
+    Cancelled,
+
+    /// A backup completed, but was unsuccessful
+    #[error("Backup Failed")]
+    BackupFailed,
+
+    /// The installation of a server failed
+    #[error("Install Failed")]
+    InstallFailed,
+
+    /// An operation didn't complete within the given timeout
+    #[error("Timeout")]
+    Timeout,
+
+    /// A string was expected to be a data URI, but could not be decoded as one
+    #[error("Invalid Data URI")]
+    InvalidDataUri,
+
+    /// An uploaded file was not found on the server afterwards, or had the wrong size
+    #[error("Upload Verification Failed")]
+    UploadVerificationFailed,
+
+    /// The panel responded with an empty body where content was expected
+    #[error("Empty Response")]
+    EmptyResponse,
+
+    /// A required field was empty
+    #[error("Missing Field: {0}")]
+    MissingField(&'static str),
+
+    /// The operation is not supported by the client API of the panel. The value names the
+    /// alternative to use instead
+    #[error("Unsupported: use {0} instead")]
+    Unsupported(&'static str),
+}
+
+impl Error {
+    /// Returns whether the operation which produced this error may succeed if it is retried
+    /// later, for example after a rate limit or a temporary network or server failure. Errors
+    /// caused by the request itself, such as permission or validation errors, are not retryable
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::RateLimit => true,
+            Error::Reqwest(err) => {
+                err.is_connect()
+                    || err.is_timeout()
+                    || err.status().is_some_and(|status| status.is_server_error())
+            }
+            _ => self.is_transient_http(),
+        }
+    }
+
+    /// Returns whether this error is a [`Error::Http`] error with a 5xx status code, which
+    /// indicates a server-side failure that may be temporary
+    pub fn is_transient_http(&self) -> bool {
+        matches!(self, Error::Http(status) if status.is_server_error())
+    }
+}
+
+/// Runs the given operation until it completes or the given token is cancelled, in which case
+/// [`Error::Cancelled`] is returned. This is mostly useful for operations made of multiple
+/// requests, such as [`client::Server::wait_for_state`] or [`client::Server::wait_for_backup`],
+/// which can then be cancelled from elsewhere, for example from a "stop" button in a UI.
+///
+/// Cancellation drops the operation at the point it is currently waiting at, like dropping any
+/// other future. Any request in flight is aborted and no further requests are made, but changes
+/// already made by the operation are not undone: for example, a backup which was already created
+/// is not deleted, and a power signal which was already sent still takes effect. If the token is
+/// already cancelled, the operation is not started at all
+///
+/// ```no_run
+/// # use pterodactyl_api::client::{Server, ServerState};
+/// # use std::time::Duration;
+/// # use tokio_util::sync::CancellationToken;
+/// # let server: Server = todo!();
+/// # async {
+/// let token = CancellationToken::new();
+/// // call token.cancel() elsewhere to stop waiting
+/// pterodactyl_api::cancellable(
+///     &token,
+///     server.wait_for_state(ServerState::Running, Duration::from_secs(60), Duration::from_secs(1)),
+/// )
+/// .await?;
+/// # Ok::<_, pterodactyl_api::Error>(())
+/// # };
+/// ```
+#[cfg(feature = "cancellation")]
+pub async fn cancellable<T>(
+    token: &tokio_util::sync::CancellationToken,
+    operation: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    tokio::select! {
+        biased;
+        () = token.cancelled() => Err(Error::Cancelled),
+        result = operation => result,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Error;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(Error::RateLimit.is_retryable());
+        assert!(Error::Http(StatusCode::BAD_GATEWAY).is_retryable());
+        assert!(Error::Http(StatusCode::SERVICE_UNAVAILABLE).is_transient_http());
+        assert!(!Error::Http(StatusCode::BAD_REQUEST).is_retryable());
+        assert!(!Error::RateLimit.is_transient_http());
+        assert!(!Error::PermissionError.is_retryable());
+        assert!(!Error::MissingField("name").is_retryable());
+    }
+
+    #[cfg(feature = "cancellation")]
+    #[tokio::test]
+    async fn test_cancellable() {
+        use tokio_util::sync::CancellationToken;
+
+        let token = CancellationToken::new();
+        assert_eq!(
+            crate::cancellable(&token, async { Ok(1) }).await.unwrap(),
+            1
+        );
+        let pending = crate::cancellable(&token, std::future::pending::<crate::Result<()>>());
+        token.cancel();
+        assert!(matches!(pending.await, Err(Error::Cancelled)));
+        assert!(matches!(
+            crate::cancellable(&token, async { Ok(()) }).await,
+            Err(Error::Cancelled)
+        ));
+    }
 }
@@ -1,5 +1,5 @@
 use serde::de::DeserializeOwned;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use time::format_description::well_known::Iso8601;
 use time::OffsetDateTime;
 
@@ -16,6 +16,15 @@ where
         .map_err(|err| <D::Error as serde::de::Error>::custom(format!("{err}")))
 }
 
+pub(crate) fn serialize_iso_time<S>(time: &OffsetDateTime, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    time.format(&Iso8601::DEFAULT)
+        .map_err(|err| <S::Error as serde::ser::Error>::custom(format!("{err}")))?
+        .serialize(serializer)
+}
+
 pub(crate) fn optional_iso_time<'de, D>(deserializer: D) -> Result<Option<OffsetDateTime>, D::Error>
 where
     D: Deserializer<'de>,
@@ -27,7 +36,20 @@ where
         .map_err(|err| <D::Error as serde::de::Error>::custom(format!("{err}")))
 }
 
-#[derive(Deserialize)]
+pub(crate) fn serialize_optional_iso_time<S>(
+    time: &Option<OffsetDateTime>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match time {
+        Some(time) => serialize_iso_time(time, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[derive(Deserialize, Serialize)]
 pub(crate) struct PteroObject<T> {
     pub(crate) attributes: T,
 }
@@ -56,6 +78,51 @@ where
     Ok(option.unwrap_or_default())
 }
 
+// Deserializes a JSON object of strings into its key-value pairs, preserving their order. PHP
+// serializes empty maps as empty arrays, so those are accepted too
+pub(crate) fn ordered_string_map<'de, D>(deserializer: D) -> Result<Vec<(String, String)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct OrderedStringMapVisitor;
+    impl<'de> serde::de::Visitor<'de> for OrderedStringMapVisitor {
+        type Value = Vec<(String, String)>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            formatter.write_str("a map of strings")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+        {
+            let mut entries = Vec::new();
+            while let Some(entry) = map.next_entry()? {
+                entries.push(entry);
+            }
+            Ok(entries)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            match seq.next_element::<serde::de::IgnoredAny>()? {
+                Some(_) => Err(serde::de::Error::invalid_type(
+                    serde::de::Unexpected::Seq,
+                    &self,
+                )),
+                None => Ok(Vec::new()),
+            }
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E> {
+            Ok(Vec::new())
+        }
+    }
+    deserializer.deserialize_any(OrderedStringMapVisitor)
+}
+
 pub(crate) fn ptero_list<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
 where
     D: Deserializer<'de>,
@@ -72,3 +139,71 @@ where
         .map(|obj| obj.attributes)
         .collect())
 }
+
+pub(crate) fn serialize_ptero_list<S, T>(list: &[T], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    #[derive(Serialize)]
+    struct PteroList<'a, T> {
+        data: Vec<PteroObject<&'a T>>,
+    }
+    PteroList {
+        data: list
+            .iter()
+            .map(|attributes| PteroObject { attributes })
+            .collect(),
+    }
+    .serialize(serializer)
+}
+
+pub(crate) fn optional_ptero_list<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    struct PteroList<T> {
+        data: Vec<PteroObject<T>>,
+    }
+    let ptero_list: Option<PteroList<T>> = Deserialize::deserialize(deserializer)?;
+    Ok(ptero_list.map(|list| list.data.into_iter().map(|obj| obj.attributes).collect()))
+}
+
+pub(crate) fn serialize_optional_ptero_list<S, T>(
+    list: &Option<Vec<T>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    match list {
+        Some(list) => serialize_ptero_list(list, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub(crate) fn optional_ptero_object<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let object: Option<PteroObject<T>> = Deserialize::deserialize(deserializer)?;
+    Ok(object.map(|obj| obj.attributes))
+}
+
+pub(crate) fn serialize_optional_ptero_object<S, T>(
+    object: &Option<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    object
+        .as_ref()
+        .map(|attributes| PteroObject { attributes })
+        .serialize(serializer)
+}